@@ -53,10 +53,31 @@ mod gltf;
 
 #[cfg(feature = "image")]
 mod img;
+#[cfg(feature = "png")]
+pub use img::PngMetadata;
+#[cfg(feature = "image")]
+pub use img::{deserialize_img_with_options, image_format_of_bytes, DecodeOptions};
+#[cfg(feature = "hdr")]
+pub use img::{hdr_image_from_bytes, HdrOptions};
 
 #[cfg(feature = "vol")]
 mod vol;
 
+mod ktx2;
+
+#[cfg(feature = "psd")]
+mod psd;
+#[cfg(feature = "psd")]
+pub use psd::psd_from_bytes;
+
+#[cfg(any(feature = "png", feature = "gif"))]
+mod indexed;
+
+#[cfg(feature = "exr")]
+mod exr;
+#[cfg(feature = "exr")]
+pub use exr::exr_layer_from_bytes;
+
 #[cfg(feature = "pcd")]
 mod pcd;
 
@@ -77,6 +98,53 @@ pub async fn load_and_deserialize_async<T: Deserialize>(
     load_async(&[&path]).await?.deserialize(path)
 }
 
+///
+/// Deserialize the given bytes into a [Texture2D](crate::Texture2D) and applies the given filter, wrapping and mipmap options to it.
+/// The path is used as the name of the texture and to determine the image format if it cannot be detected from the bytes.
+///
+pub fn deserialize_texture_with_options(
+    path: impl AsRef<std::path::Path>,
+    bytes: Vec<u8>,
+    options: &crate::TextureOptions,
+) -> crate::Result<crate::Texture2D> {
+    let mut raw_assets = RawAssets::new();
+    raw_assets.insert(&path, bytes);
+    raw_assets.deserialize_texture_with_options(path, options)
+}
+
+///
+/// Downloads and deserialize the texture at the given url, blocking the current thread until it is done.
+/// Responses with a status code that is not a success results in a [FailedLoadingUrlWithStatusCode](crate::Error::FailedLoadingUrlWithStatusCode) error.
+///
+/// **Note:** This function must not be called from within an async runtime, use [load_url_async] instead.
+///
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+pub fn load_url(url: &str) -> crate::Result<crate::Texture2D> {
+    let response = reqwest::blocking::get(url)
+        .map_err(|e| Error::FailedLoadingUrlWithReqwest(url.to_string(), e))?;
+    if !response.status().is_success() {
+        Err(Error::FailedLoadingUrlWithStatusCode(
+            url.to_string(),
+            response.status().as_u16(),
+        ))?;
+    }
+    let bytes = response
+        .bytes()
+        .map_err(|e| Error::FailedLoadingUrlWithReqwest(url.to_string(), e))?;
+    RawAssets::new()
+        .insert(url, bytes.to_vec())
+        .deserialize(url)
+}
+
+///
+/// Async downloads and deserialize the texture at the given url.
+/// Responses with a status code that is not a success results in a [FailedLoadingUrlWithStatusCode](crate::Error::FailedLoadingUrlWithStatusCode) error.
+///
+#[cfg(feature = "reqwest")]
+pub async fn load_url_async(url: &str) -> crate::Result<crate::Texture2D> {
+    load_and_deserialize_async(url).await
+}
+
 ///
 /// Save and serialize a single file.
 ///
@@ -112,6 +180,33 @@ pub trait Serialize: Sized {
     fn serialize(&self, path: impl AsRef<std::path::Path>) -> crate::Result<RawAssets>;
 }
 
+///
+/// Implemented for assets that can be deserialized directly from the bytes of a single file,
+/// which for example makes it possible to write a generic loader that dispatches on the file extension.
+///
+/// ```
+/// # use three_d_asset::io::Asset;
+/// # use three_d_asset::Texture2D;
+/// fn can_load<T: Asset>(path: &str) -> bool {
+///     T::supported_extensions()
+///         .iter()
+///         .any(|e| path.to_lowercase().ends_with(&format!(".{}", e)))
+/// }
+/// let is_texture = can_load::<Texture2D>("image.png");
+/// ```
+///
+pub trait Asset: Sized {
+    ///
+    /// Returns the lowercase file extensions, without the leading dot, that can be loaded with the currently enabled features.
+    ///
+    fn supported_extensions() -> &'static [&'static str];
+
+    ///
+    /// Deserialize the asset from the bytes of a single file.
+    ///
+    fn load(bytes: &[u8]) -> crate::Result<Self>;
+}
+
 use crate::{Error, Geometry, Result};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -122,6 +217,35 @@ impl Deserialize for crate::Texture2D {
         #[allow(unused_variables)]
         let bytes = raw_assets.get(&path)?;
 
+        if path.extension().map(|e| e == "psd").unwrap_or(false) {
+            #[cfg(not(feature = "psd"))]
+            return Err(Error::FeatureMissing("psd".to_string()));
+
+            #[cfg(feature = "psd")]
+            return psd::psd_from_bytes(bytes).map(|texture| crate::Texture2D {
+                name: path.to_str().unwrap().to_owned(),
+                ..texture
+            });
+        }
+
+        if path.extension().map(|e| e == "ktx2").unwrap_or(false) {
+            return crate::Texture2D::from_ktx2_bytes(bytes).map(|texture| crate::Texture2D {
+                name: path.to_str().unwrap().to_owned(),
+                ..texture
+            });
+        }
+
+        if path.extension().map(|e| e == "exr").unwrap_or(false) {
+            #[cfg(not(feature = "exr"))]
+            return Err(Error::FeatureMissing("exr".to_string()));
+
+            #[cfg(feature = "exr")]
+            return exr::deserialize_exr(bytes).map(|texture| crate::Texture2D {
+                name: path.to_str().unwrap().to_owned(),
+                ..texture
+            });
+        }
+
         #[cfg(not(feature = "image"))]
         return Err(Error::FeatureMissing(
             path.extension()
@@ -135,10 +259,119 @@ impl Deserialize for crate::Texture2D {
     }
 }
 
+impl Asset for crate::Texture2D {
+    fn supported_extensions() -> &'static [&'static str] {
+        &[
+            #[cfg(feature = "png")]
+            "png",
+            #[cfg(feature = "jpeg")]
+            "jpg",
+            #[cfg(feature = "jpeg")]
+            "jpeg",
+            #[cfg(feature = "hdr")]
+            "hdr",
+            #[cfg(feature = "tiff")]
+            "tif",
+            #[cfg(feature = "tiff")]
+            "tiff",
+            #[cfg(feature = "tga")]
+            "tga",
+            #[cfg(feature = "gif")]
+            "gif",
+            #[cfg(feature = "bmp")]
+            "bmp",
+            #[cfg(feature = "psd")]
+            "psd",
+            #[cfg(feature = "exr")]
+            "exr",
+            "ktx2",
+        ]
+    }
+
+    ///
+    /// The image format is detected from the bytes.
+    ///
+    #[allow(unused_variables)]
+    fn load(bytes: &[u8]) -> Result<Self> {
+        #[cfg(feature = "psd")]
+        if bytes.starts_with(b"8BPS") {
+            return psd::psd_from_bytes(bytes);
+        }
+
+        #[cfg(feature = "exr")]
+        if bytes.starts_with(&[0x76, 0x2f, 0x31, 0x01]) {
+            return exr::deserialize_exr(bytes);
+        }
+
+        if bytes.starts_with(&ktx2::IDENTIFIER) {
+            return crate::Texture2D::from_ktx2_bytes(bytes);
+        }
+
+        #[cfg(not(feature = "image"))]
+        return Err(Error::FeatureMissing("image".to_string()));
+
+        #[cfg(feature = "image")]
+        img::deserialize_img("", bytes)
+    }
+}
+
+impl crate::Texture2D {
+    ///
+    /// Deserialize the given bytes into a texture using the decoder given by the extension of the path,
+    /// which is useful for formats where the content alone is ambiguous.
+    /// Falls back to detecting the format from the bytes if the extension is missing or unknown.
+    /// In contrast, [RawAssets::deserialize] detects the format from the bytes first and only uses the extension as a fallback.
+    ///
+    #[allow(unused_variables)]
+    pub fn from_path_bytes(path: impl AsRef<Path>, bytes: &[u8]) -> Result<Self> {
+        let path = path.as_ref();
+        let name = path.to_string_lossy().to_string();
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy())
+            .unwrap_or_default();
+        match extension.as_ref() {
+            "psd" => {
+                #[cfg(not(feature = "psd"))]
+                return Err(Error::FeatureMissing("psd".to_string()));
+
+                #[cfg(feature = "psd")]
+                psd::psd_from_bytes(bytes).map(|texture| crate::Texture2D { name, ..texture })
+            }
+            "exr" => {
+                #[cfg(not(feature = "exr"))]
+                return Err(Error::FeatureMissing("exr".to_string()));
+
+                #[cfg(feature = "exr")]
+                exr::deserialize_exr(bytes).map(|texture| crate::Texture2D { name, ..texture })
+            }
+            "ktx2" => crate::Texture2D::from_ktx2_bytes(bytes)
+                .map(|texture| crate::Texture2D { name, ..texture }),
+            _ => {
+                #[cfg(not(feature = "image"))]
+                return Err(Error::FeatureMissing("image".to_string()));
+
+                #[cfg(feature = "image")]
+                img::deserialize_img_with_format(
+                    path,
+                    bytes,
+                    image::ImageFormat::from_path(path).ok(),
+                )
+            }
+        }
+    }
+}
+
 impl Serialize for crate::Texture2D {
     fn serialize(&self, path: impl AsRef<Path>) -> Result<RawAssets> {
         let path = path.as_ref();
 
+        if path.extension().map(|e| e == "ktx2").unwrap_or(false) {
+            let mut raw_assets = RawAssets::new();
+            raw_assets.insert(path, self.to_ktx2_bytes()?);
+            return Ok(raw_assets);
+        }
+
         #[cfg(not(feature = "image"))]
         return Err(Error::FeatureMissing(
             path.extension()
@@ -152,6 +385,49 @@ impl Serialize for crate::Texture2D {
     }
 }
 
+impl Deserialize for crate::IndexedTexture {
+    ///
+    /// Deserialize a paletted GIF or PNG image without expanding the palette.
+    /// Returns an error if the image is not paletted, use [Texture2D::to_indexed](crate::Texture2D::to_indexed) to quantize other images.
+    ///
+    fn deserialize(path: impl AsRef<Path>, raw_assets: &mut RawAssets) -> Result<Self> {
+        let path = raw_assets.match_path(path.as_ref())?;
+        #[allow(unused_variables)]
+        let bytes = raw_assets.get(&path)?;
+
+        #[cfg(not(any(feature = "png", feature = "gif")))]
+        return Err(Error::FeatureMissing(
+            path.extension()
+                .map(|e| e.to_str().unwrap())
+                .unwrap_or("png")
+                .to_string(),
+        ));
+
+        #[cfg(any(feature = "png", feature = "gif"))]
+        indexed::deserialize_indexed(&path, bytes)
+    }
+}
+
+impl Serialize for crate::IndexedTexture {
+    ///
+    /// Serialize as a paletted GIF or PNG image depending on the extension of the path.
+    ///
+    fn serialize(&self, path: impl AsRef<Path>) -> Result<RawAssets> {
+        let path = path.as_ref();
+
+        #[cfg(not(any(feature = "png", feature = "gif")))]
+        return Err(Error::FeatureMissing(
+            path.extension()
+                .map(|e| e.to_str().unwrap())
+                .unwrap_or("png")
+                .to_string(),
+        ));
+
+        #[cfg(any(feature = "png", feature = "gif"))]
+        indexed::serialize_indexed(self, path)
+    }
+}
+
 impl Deserialize for crate::Scene {
     fn deserialize(path: impl AsRef<Path>, raw_assets: &mut RawAssets) -> Result<Self> {
         let path = raw_assets.match_path(path.as_ref())?;
@@ -205,6 +481,21 @@ impl Deserialize for crate::VoxelGrid {
     }
 }
 
+impl Asset for crate::VoxelGrid {
+    fn supported_extensions() -> &'static [&'static str] {
+        &[
+            #[cfg(feature = "vol")]
+            "vol",
+        ]
+    }
+
+    fn load(bytes: &[u8]) -> Result<Self> {
+        RawAssets::new()
+            .insert("voxel_grid.vol", bytes.to_vec())
+            .deserialize("voxel_grid.vol")
+    }
+}
+
 impl Deserialize for crate::Texture3D {
     fn deserialize(path: impl AsRef<Path>, raw_assets: &mut RawAssets) -> Result<Self> {
         let path = raw_assets.match_path(path.as_ref())?;
@@ -213,6 +504,16 @@ impl Deserialize for crate::Texture3D {
     }
 }
 
+impl Asset for crate::Texture3D {
+    fn supported_extensions() -> &'static [&'static str] {
+        crate::VoxelGrid::supported_extensions()
+    }
+
+    fn load(bytes: &[u8]) -> Result<Self> {
+        Ok(crate::VoxelGrid::load(bytes)?.voxels)
+    }
+}
+
 impl Deserialize for crate::TriMesh {
     fn deserialize(path: impl AsRef<Path>, raw_assets: &mut RawAssets) -> Result<Self> {
         let path = path.as_ref();
@@ -284,3 +585,87 @@ fn get_dependencies(raw_assets: &RawAssets) -> Vec<PathBuf> {
         .filter(|d| !raw_assets.contains_key(d))
         .collect()
 }
+
+#[cfg(test)]
+mod test {
+    ///
+    /// Serves the given status code and body for each of the given number of requests on a local port and returns the base url.
+    ///
+    #[cfg(all(feature = "reqwest", feature = "png"))]
+    fn serve(responses: Vec<(u16, Vec<u8>)>) -> String {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        });
+        url
+    }
+
+    #[cfg(all(feature = "reqwest", feature = "png"))]
+    #[test]
+    pub fn load_url() {
+        let png = include_bytes!("../test_data/test.png").to_vec();
+        let url = serve(vec![(200, png), (404, Vec::new())]);
+        let tex = super::load_url(&format!("{}/test.png", url)).unwrap();
+        assert_eq!(tex.width, 2);
+        assert_eq!(tex.height, 2);
+
+        let result = super::load_url(&format!("{}/missing.png", url));
+        assert!(matches!(
+            result,
+            Err(crate::Error::FailedLoadingUrlWithStatusCode(_, 404))
+        ));
+    }
+
+    #[cfg(all(feature = "reqwest", feature = "png"))]
+    #[tokio::test]
+    pub async fn load_url_async() {
+        let png = include_bytes!("../test_data/test.png").to_vec();
+        let url = serve(vec![(200, png), (500, Vec::new())]);
+        let tex = super::load_url_async(&format!("{}/test.png", url))
+            .await
+            .unwrap();
+        assert_eq!(tex.width, 2);
+
+        let result = super::load_url_async(&format!("{}/error.png", url)).await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::FailedLoadingUrlWithStatusCode(_, 500))
+        ));
+    }
+
+    #[test]
+    pub fn asset_extensions() {
+        use super::Asset;
+        let extensions = crate::Texture2D::supported_extensions();
+        #[cfg(feature = "png")]
+        assert!(extensions.contains(&"png"));
+        #[cfg(feature = "jpeg")]
+        assert!(extensions.contains(&"jpg") && extensions.contains(&"jpeg"));
+        #[cfg(not(feature = "image"))]
+        assert!(extensions.is_empty());
+        assert!(extensions.iter().all(|e| e.to_lowercase() == *e));
+
+        #[cfg(feature = "png")]
+        {
+            let bytes = std::fs::read("test_data/Cube_BaseColor.png").unwrap();
+            let texture = crate::Texture2D::load(&bytes).unwrap();
+            assert_eq!(texture.width, 512);
+        }
+        #[cfg(feature = "vol")]
+        assert_eq!(crate::Texture3D::supported_extensions(), &["vol"]);
+    }
+}
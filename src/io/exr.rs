@@ -0,0 +1,176 @@
+use crate::{texture::*, Error, Result};
+use ::exr::prelude::*;
+use std::io::Cursor;
+
+///
+/// Deserialize the layer with the given name from the given bytes of an .exr (OpenEXR) file into a [Texture2D] with 32-bit float data,
+/// for example to extract a single render pass (albedo, normal, depth etc.) from a multi-layer file.
+///
+/// Both layers stored as separate parts with a layer name and layers stored as channels prefixed with the layer name, like `albedo.R`, are supported.
+/// If the layer has red, green and blue channels, the result has [TextureData::RgbF32] data or [TextureData::RgbaF32] data if it also has an alpha channel.
+/// Otherwise the result has [TextureData::RF32] data containing the first channel of the layer in alphabetical order, for example the `Z` channel of a depth layer.
+///
+/// Returns an [ExrLayerNotFound](crate::Error::ExrLayerNotFound) error, which lists the available layers, if no layer with the given name exists.
+///
+pub fn exr_layer_from_bytes(bytes: &[u8], layer: &str) -> Result<Texture2D> {
+    let layers = exr_layers(bytes)?;
+    match layers.iter().find(|l| l.name == layer) {
+        Some(l) => Ok(l.to_texture()),
+        None => Err(Error::ExrLayerNotFound(
+            layer.to_owned(),
+            layers
+                .iter()
+                .map(|l| l.name.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )),
+    }
+}
+
+///
+/// Deserialize the first layer of the given bytes of an .exr file, see [exr_layer_from_bytes].
+///
+pub fn deserialize_exr(bytes: &[u8]) -> Result<Texture2D> {
+    exr_layers(bytes)?
+        .first()
+        .map(|l| l.to_texture())
+        .ok_or(Error::ExrCorruptData)
+}
+
+struct ExrLayer {
+    name: String,
+    width: u32,
+    height: u32,
+    /// The channel names without the layer name prefix together with the samples, in alphabetical order.
+    channels: Vec<(String, Vec<f32>)>,
+}
+
+impl ExrLayer {
+    fn to_texture(&self) -> Texture2D {
+        let channel = |name: &str| {
+            self.channels
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, values)| values)
+        };
+        let data = match (channel("R"), channel("G"), channel("B"), channel("A")) {
+            (Some(r), Some(g), Some(b), Some(a)) => {
+                TextureData::RgbaF32((0..r.len()).map(|i| [r[i], g[i], b[i], a[i]]).collect())
+            }
+            (Some(r), Some(g), Some(b), None) => {
+                TextureData::RgbF32((0..r.len()).map(|i| [r[i], g[i], b[i]]).collect())
+            }
+            _ => TextureData::RF32(self.channels[0].1.clone()),
+        };
+        Texture2D {
+            name: self.name.clone(),
+            data,
+            width: self.width,
+            height: self.height,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Reads all layers of the file, where channels named `layer.channel` are grouped into a layer named `layer`.
+/// Channels without a prefix belong to the layer given by the layer name attribute or, if it is missing, a layer with an empty name.
+///
+fn exr_layers(bytes: &[u8]) -> Result<Vec<ExrLayer>> {
+    let image = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .all_layers()
+        .all_attributes()
+        .from_buffered(Cursor::new(bytes))?;
+    let mut layers: Vec<ExrLayer> = Vec::new();
+    for layer in image.layer_data.iter() {
+        let part_name = layer
+            .attributes
+            .layer_name
+            .as_ref()
+            .map(|n| n.to_string())
+            .unwrap_or_default();
+        for channel in layer.channel_data.list.iter() {
+            let full_name = channel.name.to_string();
+            let (name, channel_name) = match full_name.rsplit_once('.') {
+                Some((prefix, suffix)) if part_name.is_empty() => {
+                    (prefix.to_owned(), suffix.to_owned())
+                }
+                Some((prefix, suffix)) => (format!("{}.{}", part_name, prefix), suffix.to_owned()),
+                None => (part_name.clone(), full_name.clone()),
+            };
+            let values = channel.sample_data.values_as_f32().collect();
+            match layers.iter_mut().find(|l| l.name == name) {
+                Some(l) => l.channels.push((channel_name, values)),
+                None => layers.push(ExrLayer {
+                    name,
+                    width: layer.size.width() as u32,
+                    height: layer.size.height() as u32,
+                    channels: vec![(channel_name, values)],
+                }),
+            }
+        }
+    }
+    Ok(layers)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn two_layer_exr() -> Vec<u8> {
+        let layer = |name: &str, channels: Vec<AnyChannel<FlatSamples>>| {
+            Layer::new(
+                (2, 1),
+                LayerAttributes::named(name),
+                Encoding::FAST_LOSSLESS,
+                AnyChannels::sort(channels.into()),
+            )
+        };
+        let albedo = layer(
+            "albedo",
+            vec![
+                AnyChannel::new("R", FlatSamples::F32(vec![1.0, 0.0])),
+                AnyChannel::new("G", FlatSamples::F32(vec![0.5, 0.0])),
+                AnyChannel::new("B", FlatSamples::F32(vec![0.25, 1.0])),
+            ],
+        );
+        let depth = layer(
+            "depth",
+            vec![AnyChannel::new("Z", FlatSamples::F32(vec![10.0, 20.5]))],
+        );
+        let image = Image::from_layers(
+            ImageAttributes::new(IntegerBounds::from_dimensions((2, 1))),
+            vec![albedo, depth],
+        );
+        let mut bytes = Vec::new();
+        image.write().to_buffered(Cursor::new(&mut bytes)).unwrap();
+        bytes
+    }
+
+    #[test]
+    pub fn layer_by_name() {
+        let bytes = two_layer_exr();
+
+        let albedo = exr_layer_from_bytes(&bytes, "albedo").unwrap();
+        assert_eq!((albedo.width, albedo.height), (2, 1));
+        assert_eq!(
+            albedo.data,
+            TextureData::RgbF32(vec![[1.0, 0.5, 0.25], [0.0, 0.0, 1.0]])
+        );
+
+        let depth = exr_layer_from_bytes(&bytes, "depth").unwrap();
+        assert_eq!(depth.data, TextureData::RF32(vec![10.0, 20.5]));
+
+        match exr_layer_from_bytes(&bytes, "normal") {
+            Err(Error::ExrLayerNotFound(name, available)) => {
+                assert_eq!(name, "normal");
+                assert_eq!(available, "albedo, depth");
+            }
+            _ => panic!("expected a layer not found error"),
+        }
+        assert_eq!(deserialize_exr(&bytes).unwrap().data, albedo.data);
+    }
+}
@@ -371,7 +371,7 @@ fn parse_texture<'a>(
                 raw_assets.deserialize(path.join(uri))?
             }
         }
-        ::gltf::image::Source::View { view, .. } => {
+        ::gltf::image::Source::View { view, mime_type } => {
             if view.stride() != None {
                 unimplemented!();
             }
@@ -380,7 +380,7 @@ fn parse_texture<'a>(
             #[cfg(not(feature = "image"))]
             return Err(Error::FeatureMissing("image".to_string()));
             #[cfg(feature = "image")]
-            super::img::deserialize_img("", &buffer[view.offset()..view.offset() + view.length()])?
+            Texture2D::from_gltf_buffer_view(buffer, view.offset(), view.length(), mime_type)?
         }
     };
 
@@ -3,79 +3,786 @@ use image::{io::Reader, *};
 use std::io::Cursor;
 use std::path::Path;
 
+///
+/// Returns the image format detected from the magic bytes at the start of the given byte array or `None` if the format is not recognized.
+/// This does not decode any pixel data and is therefore cheap, which makes it useful for deciding how to handle an image before decoding it,
+/// for example to tell high dynamic range images apart from low dynamic range images.
+///
+pub fn image_format_of_bytes(bytes: &[u8]) -> Option<ImageFormat> {
+    Reader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .format()
+}
+
 pub fn deserialize_img(path: impl AsRef<Path>, bytes: &[u8]) -> Result<Texture2D> {
+    deserialize_img_with_format(path, bytes, None)
+}
+
+///
+/// Deserialize the image bytes using the given format or, if no format is given, the format detected from the bytes or the extension of the path.
+///
+/// Returns a [Truncated](crate::Error::Truncated) error if the bytes end before the image is complete, for example because of a partial download.
+///
+pub fn deserialize_img_with_format(
+    path: impl AsRef<Path>,
+    bytes: &[u8],
+    format: Option<ImageFormat>,
+) -> Result<Texture2D> {
     let name = path
         .as_ref()
         .to_str()
         .filter(|s| !s.starts_with("data:"))
         .unwrap_or("default")
         .to_owned();
-    let mut reader = Reader::new(Cursor::new(bytes))
-        .with_guessed_format()
-        .expect("Cursor io never fails");
+    decode_img(path.as_ref(), name.clone(), bytes, format).map_err(|e| {
+        if is_unexpected_end(&e) {
+            Error::Truncated(name, bytes.len())
+        } else {
+            e
+        }
+    })
+}
+
+///
+/// Returns whether the error is caused by the data ending unexpectedly.
+///
+fn is_unexpected_end(error: &Error) -> bool {
+    let io_error = match error {
+        Error::IO(e) | Error::Image(ImageError::IoError(e)) => e,
+        #[cfg(feature = "tiff")]
+        Error::Tiff(tiff::TiffError::IoError(e)) => e,
+        _ => return false,
+    };
+    io_error.kind() == std::io::ErrorKind::UnexpectedEof
+}
+
+fn decode_img(
+    path: &Path,
+    name: String,
+    bytes: &[u8],
+    format: Option<ImageFormat>,
+) -> Result<Texture2D> {
+    let mut reader = Reader::new(Cursor::new(bytes));
+    if let Some(format) = format {
+        reader.set_format(format);
+    } else {
+        reader = reader.with_guessed_format()?;
+    }
 
     if reader.format().is_none() {
         reader.set_format(ImageFormat::from_path(path)?);
     }
     #[cfg(feature = "hdr")]
     if reader.format() == Some(image::ImageFormat::Hdr) {
-        use image::codecs::hdr::*;
-        let decoder = HdrDecoder::new(&*bytes)?;
-        let metadata = decoder.metadata();
-        let img = decoder.read_image_native()?;
         return Ok(Texture2D {
             name,
-            data: TextureData::RgbF32(
-                img.iter()
-                    .map(|rgbe| {
-                        let Rgb(values) = rgbe.to_hdr();
-                        [values[0], values[1], values[2]]
-                    })
-                    .collect::<Vec<_>>(),
-            ),
-            width: metadata.width,
-            height: metadata.height,
-            ..Default::default()
+            ..hdr_image_from_bytes(bytes, &HdrOptions::default())?
         });
     }
+    #[cfg(feature = "jpeg")]
+    if reader.format() == Some(ImageFormat::Jpeg) {
+        if let Some((width, height, data)) = deserialize_cmyk_jpeg(&name, bytes)? {
+            return Ok(Texture2D {
+                name,
+                data,
+                width,
+                height,
+                ..Default::default()
+            });
+        }
+    }
+    #[cfg(feature = "tiff")]
+    if reader.format() == Some(ImageFormat::Tiff) {
+        if let Some((width, height, data)) = match deserialize_float_tiff(bytes)? {
+            Some(result) => Some(result),
+            None => deserialize_bilevel_tiff(bytes)?,
+        } {
+            return Ok(Texture2D {
+                name,
+                data,
+                width,
+                height,
+                ..Default::default()
+            });
+        }
+    }
     let img: DynamicImage = reader.decode()?;
     let width = img.width();
     let height = img.height();
-    let data = match img {
+    let data = texture_data_from_dynamic_image(img);
+    Ok(Texture2D {
+        name,
+        data,
+        width,
+        height,
+        ..Default::default()
+    })
+}
+
+///
+/// Options for decoding images using [deserialize_img_with_options].
+///
+/// **Note:** The number of decoder threads cannot be controlled. With `image` 0.24, the JPEG decoder (`jpeg-decoder` 0.3) decides on its own
+/// and decodes the color components of large images on separate threads on native targets, while all decoders use a single thread on web.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DecodeOptions {
+    /// The maximum time decoding is allowed to take, for example to detect pathologically slow files on low-power devices.
+    /// The limit is only checked after decoding has finished, so a slow file is not interrupted and still takes the full decoding time,
+    /// but a [DecodeTimeExceeded](crate::Error::DecodeTimeExceeded) error is returned instead of the texture if decoding took longer.
+    /// The limit is ignored on web, where the time cannot be measured without access to the browser. Default is `None`.
+    pub max_decode_time: Option<std::time::Duration>,
+}
+
+///
+/// Deserialize the image bytes like [deserialize_img_with_format] without a given format, but using the given [DecodeOptions].
+///
+#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+pub fn deserialize_img_with_options(
+    path: impl AsRef<Path>,
+    bytes: &[u8],
+    options: &DecodeOptions,
+) -> Result<Texture2D> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let start = std::time::Instant::now();
+    let texture = deserialize_img_with_format(path.as_ref(), bytes, None)?;
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(limit) = options.max_decode_time {
+        let elapsed = start.elapsed();
+        if elapsed > limit {
+            return Err(Error::DecodeTimeExceeded(
+                path.as_ref().to_string_lossy().to_string(),
+                elapsed,
+                limit,
+            ));
+        }
+    }
+    Ok(texture)
+}
+
+///
+/// Options for decoding Radiance HDR (.hdr) images using [hdr_image_from_bytes].
+///
+#[cfg(feature = "hdr")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HdrOptions {
+    /// Whether to output [TextureData::RgbaF32] data with an alpha value of `1.0` instead of [TextureData::RgbF32] data,
+    /// for example for pipelines that only handle four channel textures. Default is `false`.
+    pub alpha: bool,
+    /// The scale which the decoded color values are multiplied with, for example to adjust the exposure of an environment map. Default is `1.0`.
+    pub exposure: f32,
+}
+
+#[cfg(feature = "hdr")]
+impl Default for HdrOptions {
+    fn default() -> Self {
+        Self {
+            alpha: false,
+            exposure: 1.0,
+        }
+    }
+}
+
+///
+/// Decodes the given bytes of a Radiance HDR (.hdr) image into a [Texture2D] with 32-bit float data using the given options.
+/// Deserializing an .hdr file using [RawAssets::deserialize](crate::io::RawAssets::deserialize) is the same as using the default options.
+///
+#[cfg(feature = "hdr")]
+pub fn hdr_image_from_bytes(bytes: &[u8], options: &HdrOptions) -> Result<Texture2D> {
+    use image::codecs::hdr::*;
+    let decoder = HdrDecoder::new(bytes)?;
+    let metadata = decoder.metadata();
+    let img = decoder.read_image_native()?;
+    let colors = img.iter().map(|rgbe| {
+        let Rgb(values) = rgbe.to_hdr();
+        values.map(|v| v * options.exposure)
+    });
+    Ok(Texture2D {
+        data: if options.alpha {
+            TextureData::RgbaF32(colors.map(|[r, g, b]| [r, g, b, 1.0]).collect())
+        } else {
+            TextureData::RgbF32(colors.collect())
+        },
+        width: metadata.width,
+        height: metadata.height,
+        ..Default::default()
+    })
+}
+
+fn texture_data_from_dynamic_image(img: DynamicImage) -> TextureData {
+    match img {
         DynamicImage::ImageLuma8(_) => TextureData::RU8(img.into_bytes()),
-        DynamicImage::ImageLumaA8(img) => TextureData::RgU8(
+        DynamicImage::ImageLumaA8(img) => TextureData::RgU8(img.pixels().map(|p| p.0).collect()),
+        DynamicImage::ImageRgb8(img) => TextureData::RgbU8(
+            img.into_raw()
+                .chunks(3)
+                .map(|c| [c[0], c[1], c[2]])
+                .collect::<Vec<_>>(),
+        ),
+        DynamicImage::ImageRgba8(img) => TextureData::RgbaU8(
             img.into_raw()
-                .chunks(2)
-                .map(|c| [c[0], c[1]])
+                .chunks(4)
+                .map(|c| [c[0], c[1], c[2], c[3]])
                 .collect::<Vec<_>>(),
         ),
-        DynamicImage::ImageRgb8(img) => TextureData::RgbU8(
+        DynamicImage::ImageLuma16(img) => TextureData::RU16(img.into_raw()),
+        DynamicImage::ImageLumaA16(img) => TextureData::RgU16(img.pixels().map(|p| p.0).collect()),
+        DynamicImage::ImageRgb16(img) => TextureData::RgbU16(
             img.into_raw()
                 .chunks(3)
                 .map(|c| [c[0], c[1], c[2]])
                 .collect::<Vec<_>>(),
         ),
-        DynamicImage::ImageRgba8(img) => TextureData::RgbaU8(
+        DynamicImage::ImageRgba16(img) => TextureData::RgbaU16(
+            img.into_raw()
+                .chunks(4)
+                .map(|c| [c[0], c[1], c[2], c[3]])
+                .collect::<Vec<_>>(),
+        ),
+        DynamicImage::ImageRgb32F(img) => TextureData::RgbF32(
+            img.into_raw()
+                .chunks(3)
+                .map(|c| [c[0], c[1], c[2]])
+                .collect::<Vec<_>>(),
+        ),
+        DynamicImage::ImageRgba32F(img) => TextureData::RgbaF32(
             img.into_raw()
                 .chunks(4)
                 .map(|c| [c[0], c[1], c[2], c[3]])
                 .collect::<Vec<_>>(),
         ),
-        _ => unimplemented!(),
+        // Formats added to the image crate in the future are converted to 32-bit float instead of failing.
+        img => TextureData::RgbaF32(
+            img.into_rgba32f()
+                .into_raw()
+                .chunks(4)
+                .map(|c| [c[0], c[1], c[2], c[3]])
+                .collect::<Vec<_>>(),
+        ),
+    }
+}
+
+///
+/// Decodes a TIFF with 32-bit float samples, which is not supported by the image crate, to float [TextureData] to keep the full precision.
+/// Returns `None` if the samples are not floating point.
+///
+#[cfg(feature = "tiff")]
+fn deserialize_float_tiff(bytes: &[u8]) -> Result<Option<(u32, u32, TextureData)>> {
+    use tiff::decoder::{Decoder, DecodingResult};
+    use tiff::tags::{SampleFormat, Tag};
+    use tiff::ColorType;
+    let mut decoder = Decoder::new(Cursor::new(bytes))?;
+    let sample_format = decoder.find_tag_unsigned_vec::<u16>(Tag::SampleFormat)?;
+    if sample_format.and_then(|f| f.first().copied()) != Some(SampleFormat::IEEEFP.to_u16()) {
+        return Ok(None);
+    }
+    let (width, height) = decoder.dimensions()?;
+    let color_type = decoder.colortype()?;
+    let values = match decoder.read_image()? {
+        DecodingResult::F32(values) => values,
+        _ => return Ok(None),
+    };
+    let channels = match color_type {
+        ColorType::Gray(32) => 1,
+        ColorType::GrayA(32) => 2,
+        ColorType::RGB(32) => 3,
+        ColorType::RGBA(32) => 4,
+        _ => return Ok(None),
+    };
+    if values.len() != width as usize * height as usize * channels {
+        return Err(Error::InvalidBufferLength(
+            "tiff".to_owned(),
+            width as usize * height as usize * channels,
+            values.len(),
+        ));
+    }
+    let data = match color_type {
+        ColorType::Gray(32) => TextureData::RF32(values),
+        ColorType::GrayA(32) => {
+            TextureData::RgF32(values.chunks_exact(2).map(|c| [c[0], c[1]]).collect())
+        }
+        ColorType::RGB(32) => {
+            TextureData::RgbF32(values.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect())
+        }
+        ColorType::RGBA(32) => TextureData::RgbaF32(
+            values
+                .chunks_exact(4)
+                .map(|c| [c[0], c[1], c[2], c[3]])
+                .collect(),
+        ),
+        _ => return Ok(None),
     };
-    Ok(Texture2D {
-        name,
-        data,
-        width,
-        height,
-        ..Default::default()
+    Ok(Some((width, height, data)))
+}
+
+///
+/// Decodes an uncompressed TIFF with 1 bit per pixel, which is common for scanned documents and masks, into [TextureData::RU8] data
+/// where black is 0 and white is 255, taking into account whether zero is black or white in the file.
+/// The packed bits are read directly, since the tiff crate expects a whole byte per sample.
+/// Returns `None` if the image is not bilevel or not stored in uncompressed strips.
+///
+#[cfg(feature = "tiff")]
+fn deserialize_bilevel_tiff(bytes: &[u8]) -> Result<Option<(u32, u32, TextureData)>> {
+    use tiff::decoder::Decoder;
+    use tiff::tags::{CompressionMethod, PhotometricInterpretation, Tag};
+    use tiff::ColorType;
+    let mut decoder = Decoder::new(Cursor::new(bytes))?;
+    if decoder.colortype()? != ColorType::Gray(1)
+        || decoder
+            .find_tag_unsigned::<u16>(Tag::Compression)?
+            .unwrap_or(CompressionMethod::None.to_u16())
+            != CompressionMethod::None.to_u16()
+    {
+        return Ok(None);
+    }
+    let (offsets, byte_counts) = match (
+        decoder.find_tag_unsigned_vec::<u64>(Tag::StripOffsets)?,
+        decoder.find_tag_unsigned_vec::<u64>(Tag::StripByteCounts)?,
+    ) {
+        (Some(offsets), Some(byte_counts)) => (offsets, byte_counts),
+        _ => return Ok(None),
+    };
+    let white_is_zero = decoder.get_tag_unsigned::<u16>(Tag::PhotometricInterpretation)?
+        == PhotometricInterpretation::WhiteIsZero.to_u16();
+    let (width, height) = decoder.dimensions()?;
+    let mut packed = Vec::new();
+    for (offset, count) in offsets.iter().zip(byte_counts.iter()) {
+        let strip = bytes
+            .get(*offset as usize..offset.saturating_add(*count) as usize)
+            .ok_or(tiff::TiffError::FormatError(
+                tiff::TiffFormatError::InconsistentSizesEncountered,
+            ))?;
+        packed.extend_from_slice(strip);
+    }
+    // Each row starts at a new byte and the first pixel is stored in the most significant bit.
+    let row_length = (width as usize).div_ceil(8);
+    if packed.len() < row_length * height as usize {
+        return Err(tiff::TiffError::FormatError(
+            tiff::TiffFormatError::InconsistentSizesEncountered,
+        )
+        .into());
+    }
+    let values = packed
+        .chunks(row_length.max(1))
+        .take(height as usize)
+        .flat_map(|row| {
+            (0..width as usize).map(move |x| {
+                let bit = row[x / 8] >> (7 - x % 8) & 1 == 1;
+                if bit != white_is_zero {
+                    255
+                } else {
+                    0
+                }
+            })
+        })
+        .collect();
+    Ok(Some((width, height, TextureData::RU8(values))))
+}
+
+///
+/// Decodes a JPEG with CMYK or YCCK color into [TextureData::RgbU8] data.
+/// Returns `None` if the JPEG does not have four color components.
+///
+/// JPEGs written by Adobe applications, which are marked with an Adobe APP14 segment, store inverted CMYK values,
+/// while other JPEGs store the amount of ink directly. Both are converted to RGB using the naive formula without a color profile.
+///
+#[cfg(feature = "jpeg")]
+fn deserialize_cmyk_jpeg(name: &str, bytes: &[u8]) -> Result<Option<(u32, u32, TextureData)>> {
+    use jpeg_decoder::{Decoder, PixelFormat};
+    let error = |_| Error::FailedDeserialize(name.to_owned());
+    let mut decoder = Decoder::new(bytes);
+    decoder.read_info().map_err(error)?;
+    let info = decoder
+        .info()
+        .ok_or_else(|| Error::FailedDeserialize(name.to_owned()))?;
+    if info.pixel_format != PixelFormat::CMYK32 {
+        return Ok(None);
+    }
+    // The decoder always inverts the values, which results in the amount of ink only if the values were inverted to begin with.
+    let inverted = has_adobe_segment(bytes);
+    let values = decoder.decode().map_err(error)?;
+    let data = TextureData::RgbU8(
+        values
+            .chunks_exact(4)
+            .map(|c| {
+                let paper = |v: u8| if inverted { 255 - v as u32 } else { v as u32 };
+                let white = paper(c[3]);
+                [0, 1, 2].map(|i| ((paper(c[i]) * white + 127) / 255) as u8)
+            })
+            .collect(),
+    );
+    Ok(Some((info.width as u32, info.height as u32, data)))
+}
+
+///
+/// Returns whether the JPEG has an Adobe APP14 segment before the image data.
+///
+#[cfg(feature = "jpeg")]
+fn has_adobe_segment(bytes: &[u8]) -> bool {
+    let mut i = 2;
+    while i + 4 <= bytes.len() && bytes[i] == 0xFF {
+        let marker = bytes[i + 1];
+        if marker == 0xFF {
+            i += 1;
+            continue;
+        }
+        if marker == 0xDA {
+            break;
+        }
+        let length = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+        if marker == 0xEE && bytes[i + 4..].starts_with(b"Adobe") {
+            return true;
+        }
+        i += 2 + length;
+    }
+    false
+}
+
+impl Texture2D {
+    ///
+    /// Decodes the given image bytes into the given texture, reusing the allocation of the [TextureData] of the texture
+    /// when the decoded image has the same [TextureData] variant and the capacity is large enough.
+    /// Otherwise the texture data is reallocated. Note that the decoder itself still needs a temporary buffer.
+    ///
+    /// The width, height and pixel data of the texture is replaced, the texture is marked as not [Texture2D::premultiplied],
+    /// while all other fields, like the name and sampling settings, are kept.
+    /// The image format is detected from the bytes.
+    ///
+    pub fn deserialize_into(bytes: &[u8], out: &mut Texture2D) -> Result<()> {
+        let tex = deserialize_img("", bytes)?;
+        macro_rules! reuse {
+            ($($variant:ident),*) => {
+                match (&mut out.data, tex.data) {
+                    $((TextureData::$variant(target), TextureData::$variant(source)) => {
+                        target.clear();
+                        target.extend_from_slice(&source);
+                    })*
+                    (target, source) => *target = source,
+                }
+            };
+        }
+        reuse!(
+            RU8, RgU8, RgbU8, RgbaU8, RU16, RgU16, RgbU16, RgbaU16, RF16, RgF16, RgbF16, RgbaF16,
+            RF32, RgF32, RgbF32, RgbaF32
+        );
+        out.width = tex.width;
+        out.height = tex.height;
+        out.premultiplied = false;
+        Ok(())
+    }
+}
+
+impl Texture2D {
+    ///
+    /// Decodes the given image bytes one strip of `tile` rows at a time and calls the given function with the
+    /// pixel position of the top left corner and the pixel data of each tile of at most `tile` times `tile` pixels.
+    /// The tiles are processed row by row, starting with the top left tile.
+    ///
+    /// Non-interlaced PNG images are decoded in a streaming fashion, so only a single strip of tiles is kept in memory,
+    /// which makes it possible to process huge images. Other formats are decoded completely before being split into tiles.
+    ///
+    pub fn process_tiles(
+        bytes: &[u8],
+        tile: u32,
+        mut process: impl FnMut(u32, u32, Texture2D),
+    ) -> Result<()> {
+        let tile = tile.max(1);
+        #[cfg(feature = "png")]
+        if image_format_of_bytes(bytes) == Some(ImageFormat::Png) {
+            use std::io::Read;
+            let decoder = codecs::png::PngDecoder::new(Cursor::new(bytes))?;
+            let (width, height) = decoder.dimensions();
+            let color_type = decoder.color_type();
+            #[allow(deprecated)]
+            let mut reader = decoder.into_reader()?;
+            let mut strip = vec![0; width as usize * color_type.bytes_per_pixel() as usize];
+            for y in (0..height).step_by(tile as usize) {
+                let rows = tile.min(height - y);
+                let length = (width as usize)
+                    .checked_mul(rows as usize)
+                    .and_then(|n| n.checked_mul(color_type.bytes_per_pixel() as usize))
+                    .ok_or_else(|| Error::FailedDeserialize("png".to_owned()))?;
+                strip.resize(length, 0);
+                reader.read_exact(&mut strip).map_err(ImageError::IoError)?;
+                let strip = Texture2D {
+                    data: texture_data_from_bytes(color_type, &strip)?,
+                    width,
+                    height: rows,
+                    ..Default::default()
+                };
+                for x in (0..width).step_by(tile as usize) {
+                    process(x, y, strip.crop(x, 0, tile, rows));
+                }
+            }
+            return Ok(());
+        }
+
+        let texture = deserialize_img("", bytes)?;
+        for y in (0..texture.height).step_by(tile as usize) {
+            for x in (0..texture.width).step_by(tile as usize) {
+                process(x, y, texture.crop(x, y, tile, tile));
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// Converts decoded PNG pixel bytes of the given color type, with 16-bit values in big endian as stored in the file, to [TextureData].
+///
+#[cfg(feature = "png")]
+fn texture_data_from_bytes(color_type: ColorType, bytes: &[u8]) -> Result<TextureData> {
+    let words = || -> Vec<u16> {
+        bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect()
+    };
+    Ok(match color_type {
+        ColorType::L8 => TextureData::RU8(bytes.to_vec()),
+        ColorType::La8 => TextureData::RgU8(bytes.chunks_exact(2).map(|c| [c[0], c[1]]).collect()),
+        ColorType::Rgb8 => {
+            TextureData::RgbU8(bytes.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect())
+        }
+        ColorType::Rgba8 => TextureData::RgbaU8(
+            bytes
+                .chunks_exact(4)
+                .map(|c| [c[0], c[1], c[2], c[3]])
+                .collect(),
+        ),
+        ColorType::L16 => TextureData::RU16(words()),
+        ColorType::La16 => {
+            TextureData::RgU16(words().chunks_exact(2).map(|c| [c[0], c[1]]).collect())
+        }
+        ColorType::Rgb16 => TextureData::RgbU16(
+            words()
+                .chunks_exact(3)
+                .map(|c| [c[0], c[1], c[2]])
+                .collect(),
+        ),
+        ColorType::Rgba16 => TextureData::RgbaU16(
+            words()
+                .chunks_exact(4)
+                .map(|c| [c[0], c[1], c[2], c[3]])
+                .collect(),
+        ),
+        _ => return Err(Error::FailedDeserialize("png".to_owned())),
     })
 }
 
+impl Texture2D {
+    ///
+    /// Encodes this texture in the given image format and writes it directly to the given writer,
+    /// which avoids keeping the encoded bytes in memory when for example writing to a file.
+    /// See [save_texture_pixels](crate::io::save_texture_pixels) for how the pixel data is converted.
+    ///
+    pub fn write_to<W: std::io::Write + std::io::Seek>(
+        &self,
+        mut writer: W,
+        format: ImageFormat,
+    ) -> Result<()> {
+        let extension = format.extensions_str().first().copied().unwrap_or("");
+        write_img(self, &mut writer, extension, Path::new(extension))
+    }
+
+    ///
+    /// Encodes this texture as a PNG image with the given textual metadata embedded as text chunks.
+    /// The pixel data is converted in the same way as when saving a PNG file, so 8-bit and 16-bit data is stored without loss.
+    /// Text which only contains Latin-1 characters is stored in a `tEXt` chunk and other text in an `iTXt` chunk.
+    ///
+    /// Returns a [FailedSerialize](crate::Error::FailedSerialize) error if a keyword is empty, too long or not Latin-1.
+    ///
+    #[cfg(feature = "png")]
+    pub fn to_png_bytes_with_metadata(&self, metadata: &PngMetadata) -> Result<Vec<u8>> {
+        let error = |_| Error::FailedSerialize(self.name.clone());
+        let img =
+            dynamic_image_from_texture_data(self.width, self.height, fixed_point_data(&self.data))?;
+        let (color, depth) = match img.color() {
+            image::ColorType::L8 => (png::ColorType::Grayscale, png::BitDepth::Eight),
+            image::ColorType::La8 => (png::ColorType::GrayscaleAlpha, png::BitDepth::Eight),
+            image::ColorType::Rgb8 => (png::ColorType::Rgb, png::BitDepth::Eight),
+            image::ColorType::Rgba8 => (png::ColorType::Rgba, png::BitDepth::Eight),
+            image::ColorType::L16 => (png::ColorType::Grayscale, png::BitDepth::Sixteen),
+            image::ColorType::La16 => (png::ColorType::GrayscaleAlpha, png::BitDepth::Sixteen),
+            image::ColorType::Rgb16 => (png::ColorType::Rgb, png::BitDepth::Sixteen),
+            image::ColorType::Rgba16 => (png::ColorType::Rgba, png::BitDepth::Sixteen),
+            _ => return Err(Error::TextureDataFormatMismatch),
+        };
+        let pixels = if depth == png::BitDepth::Sixteen {
+            // PNG stores 16-bit values in big endian.
+            img.as_bytes()
+                .chunks_exact(2)
+                .flat_map(|c| u16::from_ne_bytes([c[0], c[1]]).to_be_bytes())
+                .collect()
+        } else {
+            img.as_bytes().to_vec()
+        };
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+            encoder.set_color(color);
+            encoder.set_depth(depth);
+            for (keyword, text) in metadata.text.iter() {
+                if text.chars().all(|c| (c as u32) < 256) {
+                    encoder.add_text_chunk(keyword.clone(), text.clone())
+                } else {
+                    encoder.add_itxt_chunk(keyword.clone(), text.clone())
+                }
+                .map_err(error)?;
+            }
+            let mut writer = encoder.write_header().map_err(error)?;
+            writer.write_image_data(&pixels).map_err(error)?;
+            writer.finish().map_err(error)?;
+        }
+        Ok(bytes)
+    }
+
+    ///
+    /// Converts this texture to a [DynamicImage], applies the given function to it and converts the result back to a texture,
+    /// which gives access to all of the image processing functionality in the [image] crate, for example `|img| img.blur(2.0)` or `|img| img.huerotate(90)`.
+    /// The result has the size of the returned image and all other fields, like the name and sampling settings, are kept.
+    ///
+    /// 8-bit, 16-bit, [TextureData::RgbF32] and [TextureData::RgbaF32] data is converted without any loss,
+    /// while the other floating point formats are converted to [TextureData::RgbF32] or [TextureData::RgbaF32] if they have an alpha channel.
+    ///
+    pub fn with_dynamic_image<F: FnOnce(DynamicImage) -> DynamicImage>(
+        &self,
+        f: F,
+    ) -> Result<Texture2D> {
+        let data = match &self.data {
+            TextureData::RF16(_)
+            | TextureData::RgF16(_)
+            | TextureData::RgbF16(_)
+            | TextureData::RF32(_)
+            | TextureData::RgF32(_) => {
+                TextureData::RgbF32(Vec::new()).same_format_from_rgba_f32(self.data.iter_rgba_f32())
+            }
+            TextureData::RgbaF16(_) => TextureData::RgbaF32(Vec::new())
+                .same_format_from_rgba_f32(self.data.iter_rgba_f32()),
+            _ => self.data.clone(),
+        };
+        let img = f(dynamic_image_from_texture_data(
+            self.width,
+            self.height,
+            data,
+        )?);
+        Ok(Texture2D {
+            width: img.width(),
+            height: img.height(),
+            data: texture_data_from_dynamic_image(img),
+            ..self.clone()
+        })
+    }
+
+    ///
+    /// Encodes this texture in the given image format and returns it as a base64 encoded `data:` URL,
+    /// for example to embed the image in HTML, CSS or a JSON document. See [Texture2D::from_data_url] for the reverse.
+    ///
+    pub fn to_data_url(&self, format: ImageFormat) -> Result<String> {
+        let mut bytes = Vec::new();
+        self.write_to(Cursor::new(&mut bytes), format)?;
+        Ok(format!(
+            "data:{};base64,{}",
+            format.to_mime_type(),
+            base64_encode(&bytes)
+        ))
+    }
+
+    ///
+    /// Decodes a texture from a `data:` URL, like `data:image/png;base64,iVBORw0KGgo...`.
+    /// The image format is detected from the decoded bytes and, if that fails, from the media type of the URL.
+    /// A media type of `image/vnd.radiance` always decodes the image as a high dynamic range image.
+    ///
+    /// Returns a [FailedParsingDataUrl](crate::Error::FailedParsingDataUrl) error if the URL is malformed.
+    ///
+    #[cfg(feature = "data-url")]
+    pub fn from_data_url(url: &str) -> Result<Texture2D> {
+        let error = |e| Error::FailedParsingDataUrl(url.to_owned(), format!("{:?}", e));
+        let data_url = data_url::DataUrl::process(url).map_err(error)?;
+        let mime_type = data_url.mime_type();
+        let media_format =
+            ImageFormat::from_mime_type(format!("{}/{}", mime_type.type_, mime_type.subtype));
+        let (bytes, _) = data_url
+            .decode_to_vec()
+            .map_err(|e| Error::FailedParsingDataUrl(url.to_owned(), format!("{:?}", e)))?;
+        let format = media_format
+            .filter(|f| *f == ImageFormat::Hdr)
+            .or_else(|| image_format_of_bytes(&bytes))
+            .or(media_format)
+            .ok_or_else(|| Error::FailedDeserialize(url.to_owned()))?;
+        deserialize_img_with_format(url, &bytes, Some(format))
+    }
+
+    ///
+    /// Decodes a texture embedded in a glTF buffer, ie. the `length` bytes starting at `offset` in the given buffer as specified by a buffer view,
+    /// where the image format is given by the MIME type of the glTF image, like `image/png` or `image/jpeg`.
+    /// If the MIME type is not recognized, the format is detected from the bytes.
+    ///
+    /// Returns an [InvalidBufferLength](crate::Error::InvalidBufferLength) error if the buffer view is outside the buffer.
+    ///
+    pub fn from_gltf_buffer_view(
+        buffer: &[u8],
+        offset: usize,
+        length: usize,
+        mime: &str,
+    ) -> Result<Texture2D> {
+        let bytes = offset
+            .checked_add(length)
+            .and_then(|end| buffer.get(offset..end))
+            .ok_or_else(|| {
+                Error::InvalidBufferLength(
+                    "glTF buffer view".to_owned(),
+                    offset.saturating_add(length),
+                    buffer.len(),
+                )
+            })?;
+        deserialize_img_with_format("", bytes, ImageFormat::from_mime_type(mime))
+    }
+}
+
+///
+/// Encodes the bytes as standard base64 with padding.
+///
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
 pub fn serialize_img(tex: &Texture2D, path: &Path) -> Result<RawAssets> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let mut bytes: Vec<u8> = Vec::new();
+    write_img(tex, &mut Cursor::new(&mut bytes), extension, path)?;
+    let mut raw_assets = RawAssets::new();
+    raw_assets.insert(path, bytes);
+    Ok(raw_assets)
+}
+
+fn write_img<W: std::io::Write + std::io::Seek>(
+    tex: &Texture2D,
+    writer: &mut W,
+    extension: &str,
+    path: &Path,
+) -> Result<()> {
     #![allow(unreachable_code)]
     #![allow(unused_variables)]
-    let format: image::ImageOutputFormat = match path.extension().unwrap().to_str().unwrap() {
+    let format: image::ImageOutputFormat = match extension {
         "png" => {
             #[cfg(not(feature = "png"))]
             return Err(Error::FeatureMissing("png".to_string()));
@@ -112,43 +819,164 @@ pub fn serialize_img(tex: &Texture2D, path: &Path) -> Result<RawAssets> {
             #[cfg(feature = "gif")]
             image::ImageOutputFormat::Gif
         }
-        _ => return Err(Error::FailedSerialize(path.to_str().unwrap().to_string())),
+        _ => return Err(Error::FailedSerialize(path.to_string_lossy().to_string())),
     };
-    let img = match &tex.data {
-        TextureData::RU8(data) => DynamicImage::ImageLuma8(
-            ImageBuffer::from_raw(tex.width, tex.height, data.clone()).unwrap(),
+    let is_16_bit = matches!(
+        tex.data,
+        TextureData::RU16(_)
+            | TextureData::RgU16(_)
+            | TextureData::RgbU16(_)
+            | TextureData::RgbaU16(_)
+    );
+    if is_16_bit && !matches!(extension, "png" | "tiff" | "tif") {
+        // Only PNG and TIFF can store 16 bits per channel, so saving in any other format would lose precision.
+        return Err(Error::FailedSerialize(path.to_string_lossy().to_string()));
+    }
+    if matches!(extension, "jpeg" | "jpg") && tex.data.has_alpha() {
+        // JPEG cannot store an alpha channel, so flatten the texture onto opaque black instead of just dropping the alpha channel.
+        return write_img(&tex.flatten_onto([0, 0, 0, 255]), writer, extension, path);
+    }
+    let img = dynamic_image_from_texture_data(tex.width, tex.height, fixed_point_data(&tex.data))?;
+    img.write_to(writer, format)?;
+    Ok(())
+}
+
+///
+/// Converts floating point data to 8-bit data with the same number of channels, while 8-bit and 16-bit data is returned unchanged.
+///
+fn fixed_point_data(data: &TextureData) -> TextureData {
+    match data {
+        TextureData::RF16(_) | TextureData::RF32(_) => {
+            TextureData::RU8(Vec::new()).same_format_from_rgba_f32(data.iter_rgba_f32())
+        }
+        TextureData::RgF16(_) | TextureData::RgF32(_) => {
+            TextureData::RgU8(Vec::new()).same_format_from_rgba_f32(data.iter_rgba_f32())
+        }
+        TextureData::RgbF16(_) | TextureData::RgbF32(_) => {
+            TextureData::RgbU8(Vec::new()).same_format_from_rgba_f32(data.iter_rgba_f32())
+        }
+        TextureData::RgbaF16(_) | TextureData::RgbaF32(_) => {
+            TextureData::RgbaU8(Vec::new()).same_format_from_rgba_f32(data.iter_rgba_f32())
+        }
+        _ => data.clone(),
+    }
+}
+
+///
+/// Textual metadata to embed in a PNG file using [Texture2D::to_png_bytes_with_metadata],
+/// for example the software which created the file, the color space or custom tags which tell downstream tools about the provenance of the texture.
+///
+#[cfg(feature = "png")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PngMetadata {
+    /// The keyword and text pairs, like `("Software", "my-tool 1.0")`, which are each stored in a text chunk in the given order.
+    /// A keyword must be 1-79 Latin-1 characters, while the text can be any UTF-8 string.
+    pub text: Vec<(String, String)>,
+}
+
+#[cfg(feature = "png")]
+impl PngMetadata {
+    ///
+    /// Returns the metadata with the given keyword and text pair added.
+    ///
+    pub fn with_text(mut self, keyword: impl Into<String>, text: impl Into<String>) -> Self {
+        self.text.push((keyword.into(), text.into()));
+        self
+    }
+}
+
+///
+/// Converts 8-bit, 16-bit, [TextureData::RgbF32] and [TextureData::RgbaF32] data to a [DynamicImage].
+/// Other floating point formats must be converted before calling this function, otherwise a [TextureDataFormatMismatch](crate::Error::TextureDataFormatMismatch) error is returned.
+///
+fn dynamic_image_from_texture_data(
+    width: u32,
+    height: u32,
+    data: TextureData,
+) -> Result<DynamicImage> {
+    let expected = width as usize * height as usize;
+    if data.len() != expected {
+        return Err(Error::InvalidBufferLength(
+            "texture".to_owned(),
+            expected,
+            data.len(),
+        ));
+    }
+    let length = data.len();
+    let error = || Error::InvalidBufferLength("texture".to_owned(), expected, length);
+    Ok(match data {
+        TextureData::RU16(data) => {
+            DynamicImage::ImageLuma16(ImageBuffer::from_raw(width, height, data).ok_or_else(error)?)
+        }
+        TextureData::RgU16(data) => DynamicImage::ImageLumaA16(
+            ImageBuffer::from_raw(
+                width,
+                height,
+                data.into_iter().flatten().collect::<Vec<_>>(),
+            )
+            .ok_or_else(error)?,
         ),
+        TextureData::RgbU16(data) => DynamicImage::ImageRgb16(
+            ImageBuffer::from_raw(
+                width,
+                height,
+                data.into_iter().flatten().collect::<Vec<_>>(),
+            )
+            .ok_or_else(error)?,
+        ),
+        TextureData::RgbaU16(data) => DynamicImage::ImageRgba16(
+            ImageBuffer::from_raw(
+                width,
+                height,
+                data.into_iter().flatten().collect::<Vec<_>>(),
+            )
+            .ok_or_else(error)?,
+        ),
+        TextureData::RU8(data) => {
+            DynamicImage::ImageLuma8(ImageBuffer::from_raw(width, height, data).ok_or_else(error)?)
+        }
         TextureData::RgU8(data) => DynamicImage::ImageLumaA8(
             ImageBuffer::from_raw(
-                tex.width,
-                tex.height,
-                data.iter().flat_map(|v| *v).collect::<Vec<_>>(),
+                width,
+                height,
+                data.into_iter().flatten().collect::<Vec<_>>(),
             )
-            .unwrap(),
+            .ok_or_else(error)?,
         ),
         TextureData::RgbU8(data) => DynamicImage::ImageRgb8(
             ImageBuffer::from_raw(
-                tex.width,
-                tex.height,
-                data.iter().flat_map(|v| *v).collect::<Vec<_>>(),
+                width,
+                height,
+                data.into_iter().flatten().collect::<Vec<_>>(),
             )
-            .unwrap(),
+            .ok_or_else(error)?,
         ),
         TextureData::RgbaU8(data) => DynamicImage::ImageRgba8(
             ImageBuffer::from_raw(
-                tex.width,
-                tex.height,
-                data.iter().flat_map(|v| *v).collect::<Vec<_>>(),
+                width,
+                height,
+                data.into_iter().flatten().collect::<Vec<_>>(),
             )
-            .unwrap(),
+            .ok_or_else(error)?,
         ),
-        _ => unimplemented!(),
-    };
-    let mut bytes: Vec<u8> = Vec::new();
-    img.write_to(&mut Cursor::new(&mut bytes), format)?;
-    let mut raw_assets = RawAssets::new();
-    raw_assets.insert(path, bytes);
-    Ok(raw_assets)
+        TextureData::RgbF32(data) => DynamicImage::ImageRgb32F(
+            ImageBuffer::from_raw(
+                width,
+                height,
+                data.into_iter().flatten().collect::<Vec<_>>(),
+            )
+            .ok_or_else(error)?,
+        ),
+        TextureData::RgbaF32(data) => DynamicImage::ImageRgba32F(
+            ImageBuffer::from_raw(
+                width,
+                height,
+                data.into_iter().flatten().collect::<Vec<_>>(),
+            )
+            .ok_or_else(error)?,
+        ),
+        _ => return Err(Error::TextureDataFormatMismatch),
+    })
 }
 
 #[cfg(test)]
@@ -215,6 +1043,478 @@ mod test {
         test_deserialize("png");
     }
 
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn png_16_bit() {
+        use crate::io::{RawAssets, Serialize};
+        use crate::{Texture2D, TextureData};
+        use image::{DynamicImage, ImageBuffer, ImageOutputFormat};
+        let images = [
+            DynamicImage::ImageLuma16(
+                ImageBuffer::from_raw(2, 2, vec![0, 1, 32768, 65535]).unwrap(),
+            ),
+            DynamicImage::ImageRgb16(
+                ImageBuffer::from_raw(2, 1, vec![0, 1, 2, 1000, 40000, 65535]).unwrap(),
+            ),
+            DynamicImage::ImageRgba16(
+                ImageBuffer::from_raw(1, 1, vec![65535, 257, 3, 12345]).unwrap(),
+            ),
+        ];
+        for img in images {
+            let mut bytes = Vec::new();
+            img.write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                ImageOutputFormat::Png,
+            )
+            .unwrap();
+            let tex: Texture2D = RawAssets::new()
+                .insert("16bit.png", bytes)
+                .deserialize("16bit.png")
+                .unwrap();
+            match &tex.data {
+                TextureData::RU16(data) => assert_eq!(data, img.as_luma16().unwrap().as_raw()),
+                TextureData::RgbU16(data) => assert_eq!(
+                    data.iter().flatten().copied().collect::<Vec<_>>(),
+                    *img.as_rgb16().unwrap().as_raw()
+                ),
+                TextureData::RgbaU16(data) => assert_eq!(
+                    data.iter().flatten().copied().collect::<Vec<_>>(),
+                    *img.as_rgba16().unwrap().as_raw()
+                ),
+                _ => panic!("Wrong texture data: {:?}", tex.data),
+            }
+
+            let reloaded: Texture2D = tex
+                .serialize("16bit.png")
+                .unwrap()
+                .deserialize("16bit.png")
+                .unwrap();
+            assert_eq!(reloaded.data, tex.data);
+        }
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn png_luma_alpha() {
+        use crate::io::RawAssets;
+        use crate::{Texture2D, TextureData};
+        use image::{DynamicImage, ImageBuffer, ImageOutputFormat};
+        let images = [
+            DynamicImage::ImageLumaA8(ImageBuffer::from_raw(2, 1, vec![10, 255, 200, 0]).unwrap()),
+            DynamicImage::ImageLumaA16(
+                ImageBuffer::from_raw(2, 1, vec![1000, 65535, 60000, 7]).unwrap(),
+            ),
+        ];
+        let expected = [
+            TextureData::RgU8(vec![[10, 255], [200, 0]]),
+            TextureData::RgU16(vec![[1000, 65535], [60000, 7]]),
+        ];
+        for (img, expected) in images.into_iter().zip(expected) {
+            let mut bytes = Vec::new();
+            img.write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                ImageOutputFormat::Png,
+            )
+            .unwrap();
+            let tex: Texture2D = RawAssets::new()
+                .insert("luma_alpha.png", bytes)
+                .deserialize("luma_alpha.png")
+                .unwrap();
+            assert_eq!(tex.data, expected);
+            assert_eq!((tex.width, tex.height), (2, 1));
+        }
+    }
+    #[cfg(feature = "jpeg")]
+    #[test]
+    pub fn jpeg_with_alpha() {
+        use crate::io::Serialize;
+        let tex = crate::Texture2D {
+            data: crate::TextureData::RgbaU8(vec![[255, 255, 255, 0]; 64]),
+            width: 8,
+            height: 8,
+            ..Default::default()
+        };
+        let reloaded: crate::Texture2D = tex
+            .serialize("alpha.jpg")
+            .unwrap()
+            .deserialize("alpha.jpg")
+            .unwrap();
+        assert_eq!(
+            reloaded.data,
+            crate::TextureData::RgbU8(vec![[0, 0, 0]; 64])
+        );
+    }
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn write_to() {
+        let mut writer = std::io::Cursor::new(Vec::new());
+        tex()
+            .write_to(&mut writer, image::ImageFormat::Png)
+            .unwrap();
+        let bytes = writer.into_inner();
+        assert_eq!(
+            super::image_format_of_bytes(&bytes),
+            Some(image::ImageFormat::Png)
+        );
+        let reloaded = crate::Texture2D::from_path_bytes("tex.png", &bytes).unwrap();
+        assert_eq!(reloaded.data, tex().data);
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    pub fn tiff_32_bit_float() {
+        use tiff::encoder::{colortype, TiffEncoder};
+        let values = [0.125, 1.5, -2.0, 1234.5678, 0.0, 1.0e-6];
+        let mut bytes = Vec::new();
+        TiffEncoder::new(std::io::Cursor::new(&mut bytes))
+            .unwrap()
+            .write_image::<colortype::RGB32Float>(2, 1, &values)
+            .unwrap();
+
+        let tex = crate::Texture2D::from_path_bytes("float.tiff", &bytes).unwrap();
+        assert_eq!(tex.width, 2);
+        assert_eq!(tex.height, 1);
+        assert_eq!(
+            tex.data,
+            crate::TextureData::RgbF32(vec![[0.125, 1.5, -2.0], [1234.5678, 0.0, 1.0e-6]])
+        );
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn bilevel_png() {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, 10, 2);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder.set_depth(png::BitDepth::One);
+            let mut writer = encoder.write_header().unwrap();
+            writer
+                .write_image_data(&[0b1010_1010, 0b1000_0000, 0b0101_0101, 0b0100_0000])
+                .unwrap();
+        }
+        let tex = crate::Texture2D::from_path_bytes("bilevel.png", &bytes).unwrap();
+        assert_eq!((tex.width, tex.height), (10, 2));
+        assert_eq!(tex.data, checkerboard());
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    pub fn bilevel_tiff() {
+        // An uncompressed little endian TIFF with a single strip of 10x2 pixels.
+        let tiff = |photometric_interpretation: u16, rows: [u8; 4]| {
+            let mut bytes = vec![b'I', b'I', 42, 0, 12, 0, 0, 0];
+            bytes.extend(rows);
+            let entries = [
+                (256, 10),
+                (257, 2),
+                (258, 1),
+                (259, 1),
+                (262, photometric_interpretation),
+                (273, 8),
+                (277, 1),
+                (278, 2),
+                (279, 4),
+            ];
+            bytes.extend((entries.len() as u16).to_le_bytes());
+            for (tag, value) in entries {
+                bytes.extend((tag as u16).to_le_bytes());
+                bytes.extend(3u16.to_le_bytes());
+                bytes.extend(1u32.to_le_bytes());
+                bytes.extend((value as u32).to_le_bytes());
+            }
+            bytes.extend(0u32.to_le_bytes());
+            bytes
+        };
+        let black_is_zero = tiff(1, [0b1010_1010, 0b1000_0000, 0b0101_0101, 0b0100_0000]);
+        let tex = crate::Texture2D::from_path_bytes("bilevel.tiff", &black_is_zero).unwrap();
+        assert_eq!((tex.width, tex.height), (10, 2));
+        assert_eq!(tex.data, checkerboard());
+
+        let white_is_zero = tiff(0, [0b0101_0101, 0b0111_1111, 0b1010_1010, 0b1011_1111]);
+        let tex = crate::Texture2D::from_path_bytes("bilevel.tiff", &white_is_zero).unwrap();
+        assert_eq!(tex.data, checkerboard());
+    }
+
+    #[cfg(any(feature = "png", feature = "tiff"))]
+    fn checkerboard() -> crate::TextureData {
+        crate::TextureData::RU8(
+            (0..20)
+                .map(|i| if (i % 10 + i / 10) & 1 == 0 { 255 } else { 0 })
+                .collect(),
+        )
+    }
+
+    #[cfg(feature = "jpeg")]
+    #[test]
+    pub fn cmyk_jpeg() {
+        use jpeg_encoder::{ColorType, Encoder};
+        // Blocks of 8x8 texels with the amount of cyan, magenta, yellow and black ink and the expected RGB color.
+        let colors = [
+            ([0, 0, 0, 0], [255, 255, 255]),
+            ([255, 0, 0, 0], [0, 255, 255]),
+            ([0, 255, 255, 0], [255, 0, 0]),
+            ([0, 0, 0, 128], [127, 127, 127]),
+        ];
+        let encode = |invert: bool, color_type: ColorType| {
+            let mut ink = Vec::new();
+            for _ in 0..8 {
+                for (cmyk, _) in colors.iter() {
+                    for _ in 0..8 {
+                        ink.extend(cmyk.map(|v: u8| if invert { 255 - v } else { v }));
+                    }
+                }
+            }
+            let mut bytes = Vec::new();
+            Encoder::new(&mut bytes, 100)
+                .encode(&ink, 32, 8, color_type)
+                .unwrap();
+            bytes
+        };
+        let check = |bytes: &[u8]| {
+            let tex = crate::Texture2D::from_path_bytes("cmyk.jpg", bytes).unwrap();
+            assert_eq!((tex.width, tex.height), (32, 8));
+            let crate::TextureData::RgbU8(data) = tex.data else {
+                panic!("expected RgbU8 data");
+            };
+            for (i, (_, rgb)) in colors.iter().enumerate() {
+                let texel = data[3 * 32 + i * 8 + 3];
+                for c in 0..3 {
+                    assert!(texel[c].abs_diff(rgb[c]) <= 4, "{:?} != {:?}", texel, rgb);
+                }
+            }
+        };
+
+        // The encoder writes inverted values together with an Adobe segment.
+        let adobe = encode(false, ColorType::Cmyk);
+        assert!(super::has_adobe_segment(&adobe));
+        check(&adobe);
+        check(&encode(false, ColorType::CmykAsYcck));
+
+        // Without the Adobe segment, the values are the amount of ink.
+        let mut plain = encode(true, ColorType::Cmyk);
+        let start = plain
+            .windows(9)
+            .position(|w| w == b"\xFF\xEE\x00\x0EAdobe")
+            .unwrap();
+        plain.drain(start..start + 16);
+        assert!(!super::has_adobe_segment(&plain));
+        check(&plain);
+    }
+
+    #[test]
+    pub fn with_dynamic_image() {
+        use image::{imageops, DynamicImage};
+        let tex = crate::Texture2D {
+            data: crate::TextureData::RgbaU8(
+                (0..25)
+                    .map(|i| if i == 12 { [255; 4] } else { [0, 0, 0, 255] })
+                    .collect(),
+            ),
+            width: 5,
+            height: 5,
+            ..Default::default()
+        };
+        let blurred = tex
+            .with_dynamic_image(|img| DynamicImage::ImageRgba8(imageops::blur(&img, 1.0)))
+            .unwrap();
+        assert_eq!((blurred.width, blurred.height), (5, 5));
+        let crate::TextureData::RgbaU8(data) = &blurred.data else {
+            panic!("expected RgbaU8 data");
+        };
+        assert!(data[12][0] < 255 && data[12][0] > data[11][0]);
+        assert!(data[11][0] > 0 && data[11][0] == data[13][0]);
+        assert_eq!(data[12][3], 255);
+
+        let rotated = tex
+            .with_dynamic_image(|img| img.crop_imm(0, 0, 5, 2).rotate90())
+            .unwrap();
+        assert_eq!((rotated.width, rotated.height), (2, 5));
+
+        let float = crate::Texture2D {
+            data: crate::TextureData::RF32(vec![0.25, 2.0]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            float.with_dynamic_image(|img| img).unwrap().data,
+            crate::TextureData::RgbF32(vec![[0.25; 3], [2.0; 3]])
+        );
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn process_tiles() {
+        let bytes = std::fs::read("test_data/Cube_BaseColor.png").unwrap();
+        let texture = super::deserialize_img("", &bytes).unwrap();
+        let mut rebuilt = crate::Texture2D {
+            data: texture
+                .data
+                .same_format_from_rgba_f32(vec![[0.0; 4]; texture.data.len()]),
+            width: texture.width,
+            height: texture.height,
+            ..Default::default()
+        };
+        let mut tile_count = 0;
+        crate::Texture2D::process_tiles(&bytes, 100, |x, y, tile| {
+            assert!(tile.width <= 100 && tile.height <= 100);
+            rebuilt.write_region(x, y, &tile).unwrap();
+            tile_count += 1;
+        })
+        .unwrap();
+        assert_eq!(tile_count, 36);
+        assert_eq!(rebuilt.data, texture.data);
+
+        // 16-bit values are stored in big endian in the file.
+        let values: [u16; 4] = [0x0102, 0xABCD, 0x1234, 0xFFFE];
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, 2, 2);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder.set_depth(png::BitDepth::Sixteen);
+            let mut writer = encoder.write_header().unwrap();
+            writer
+                .write_image_data(
+                    &values
+                        .iter()
+                        .flat_map(|v| v.to_be_bytes())
+                        .collect::<Vec<_>>(),
+                )
+                .unwrap();
+        }
+        let texture = super::deserialize_img("", &bytes).unwrap();
+        assert_eq!(texture.data, crate::TextureData::RU16(values.to_vec()));
+        let mut rebuilt = crate::Texture2D {
+            data: crate::TextureData::RU16(vec![0; 4]),
+            width: 2,
+            height: 2,
+            ..Default::default()
+        };
+        crate::Texture2D::process_tiles(&bytes, 1, |x, y, tile| {
+            rebuilt.write_region(x, y, &tile).unwrap();
+        })
+        .unwrap();
+        assert_eq!(rebuilt.data, texture.data);
+    }
+
+    #[cfg(feature = "jpeg")]
+    #[test]
+    pub fn jpeg_16_bit() {
+        use crate::io::Serialize;
+        let tex = crate::Texture2D {
+            data: crate::TextureData::RU16(vec![0, 65535]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        assert!(tex.serialize("16bit.jpg").is_err());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn deserialize_with_options() {
+        use crate::{Interpolation, TextureOptions, Wrapping};
+        let options = TextureOptions {
+            min_filter: Interpolation::Linear,
+            mag_filter: Interpolation::Linear,
+            mip_map_filter: None,
+            wrap_s: Wrapping::ClampToEdge,
+            wrap_t: Wrapping::ClampToEdge,
+            premultiplied: false,
+        };
+        let tex = crate::io::load(&["test_data/test.png"])
+            .unwrap()
+            .deserialize_texture_with_options("test.png", &options)
+            .unwrap();
+        assert_eq!(tex.wrap_s, Wrapping::ClampToEdge);
+        assert_eq!(tex.wrap_t, Wrapping::ClampToEdge);
+        assert_eq!(tex.min_filter, Interpolation::Linear);
+        assert_eq!(tex.mag_filter, Interpolation::Linear);
+        assert_eq!(tex.mip_map_filter, None);
+
+        let tex = crate::io::deserialize_texture_with_options(
+            "test.png",
+            include_bytes!("../../test_data/test.png").to_vec(),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(tex.wrap_s, Wrapping::ClampToEdge);
+        assert_eq!(tex.mip_map_filter, None);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn deserialize_premultiplied() {
+        use crate::io::Serialize;
+        use crate::{TextureData, TextureOptions};
+        let premultiplied = crate::Texture2D {
+            data: TextureData::RgbaU8(vec![[128, 64, 0, 128], [0, 0, 0, 0]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        let mut raw_assets = premultiplied.serialize("premultiplied.png").unwrap();
+        let mut tex = raw_assets
+            .deserialize_texture_with_options(
+                "premultiplied.png",
+                &TextureOptions {
+                    premultiplied: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(tex.premultiplied);
+        assert_eq!(tex.data, premultiplied.data);
+
+        tex.unpremultiply_alpha();
+        assert!(!tex.premultiplied);
+        assert_eq!(
+            tex.data,
+            TextureData::RgbaU8(vec![[255, 128, 0, 128], [0, 0, 0, 0]])
+        );
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn save_texture_pixels() {
+        use crate::{Texture2D, TextureData};
+        let path = std::env::temp_dir().join("three-d-asset-save-texture-pixels.png");
+        for data in [
+            TextureData::RU8(vec![0, 64, 128, 255]),
+            TextureData::RgbU8(vec![[0, 0, 0], [255, 0, 0], [0, 255, 0], [0, 0, 255]]),
+        ] {
+            let tex = Texture2D {
+                data,
+                width: 2,
+                height: 2,
+                ..Default::default()
+            };
+            crate::io::save_texture_pixels(&path, &tex).unwrap();
+            let loaded: Texture2D = crate::io::load_and_deserialize(&path).unwrap();
+            assert_eq!(loaded.data, tex.data);
+        }
+
+        let tex = Texture2D {
+            data: TextureData::RgbaF32(vec![[1.0, 0.5, 0.0, 1.0]]),
+            ..Default::default()
+        };
+        crate::io::save_texture_pixels(&path, &tex).unwrap();
+        let loaded: Texture2D = crate::io::load_and_deserialize(&path).unwrap();
+        assert_eq!(loaded.data, TextureData::RgbaU8(vec![[255, 128, 0, 255]]));
+
+        // A texture which fails to encode leaves the previously saved file intact.
+        let saved = std::fs::read(&path).unwrap();
+        let invalid = Texture2D {
+            data: TextureData::RgbaU8(vec![[0; 4]; 3]),
+            width: 2,
+            height: 2,
+            ..Default::default()
+        };
+        assert!(crate::io::save_texture_pixels(&path, &invalid).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), saved);
+    }
+
     #[cfg(feature = "jpeg")]
     #[test]
     pub fn jpeg() {
@@ -254,6 +1554,55 @@ mod test {
         test_deserialize("bmp");
     }
 
+    #[test]
+    pub fn format_of_bytes() {
+        use image::ImageFormat;
+        assert_eq!(
+            super::image_format_of_bytes(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            super::image_format_of_bytes(b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n"),
+            Some(ImageFormat::Hdr)
+        );
+        assert_eq!(
+            super::image_format_of_bytes(include_bytes!("../../test_data/test.hdr")),
+            Some(ImageFormat::Hdr)
+        );
+        assert_eq!(super::image_format_of_bytes(b"not an image"), None);
+    }
+
+    #[cfg(all(feature = "hdr", feature = "png", feature = "tga"))]
+    #[test]
+    pub fn from_path_bytes() {
+        use crate::{Texture2D, TextureData};
+        let hdr_bytes = include_bytes!("../../test_data/test.hdr");
+        let tex = Texture2D::from_path_bytes("texture.hdr", hdr_bytes).unwrap();
+        assert!(matches!(tex.data, TextureData::RgbF32(_)));
+        assert_eq!(tex.name, "texture.hdr");
+
+        // The extension decides the decoder, even though the content is a PNG.
+        let png_bytes = include_bytes!("../../test_data/test.png");
+        assert!(Texture2D::from_path_bytes("texture.hdr", png_bytes).is_err());
+        assert!(Texture2D::from_path_bytes("texture.png", png_bytes).is_ok());
+
+        // Falls back to detecting the format from the content.
+        assert!(Texture2D::from_path_bytes("texture", png_bytes).is_ok());
+        assert!(Texture2D::from_path_bytes("texture.unknown", png_bytes).is_ok());
+
+        let tga_bytes = include_bytes!("../../test_data/test.tga");
+        let tex = Texture2D::from_path_bytes("texture.tga", tga_bytes).unwrap();
+        assert_eq!((tex.width, tex.height), (2, 2));
+
+        // A non-UTF-8 extension is unknown instead of a panic.
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            let path = std::path::Path::new(std::ffi::OsStr::from_bytes(b"texture.\xFF"));
+            assert!(Texture2D::from_path_bytes(path, png_bytes).is_ok());
+        }
+    }
+
     #[cfg(feature = "hdr")]
     #[test]
     pub fn hdr() {
@@ -266,4 +1615,303 @@ mod test {
         assert_eq!(tex.width, 1024);
         assert_eq!(tex.height, 512);
     }
+
+    #[cfg(feature = "hdr")]
+    #[test]
+    pub fn hdr_options() {
+        use crate::io::{hdr_image_from_bytes, HdrOptions};
+        use crate::TextureData;
+        let bytes = include_bytes!("../../test_data/test.hdr");
+        let tex = hdr_image_from_bytes(
+            bytes,
+            &HdrOptions {
+                alpha: true,
+                exposure: 2.0,
+            },
+        )
+        .unwrap();
+        assert_eq!((tex.width, tex.height), (1024, 512));
+        if let TextureData::RgbaF32(data) = tex.data {
+            assert_eq!(data[0], [0.33007812, 0.4921875, 0.40039062, 1.0]);
+            assert!(data.iter().all(|c| c[3] == 1.0));
+        } else {
+            panic!("Wrong texture data")
+        }
+
+        let tex = hdr_image_from_bytes(bytes, &HdrOptions::default()).unwrap();
+        assert!(matches!(tex.data, TextureData::RgbF32(_)));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn deserialize_into() {
+        use crate::io::Serialize;
+        let mut out = crate::Texture2D {
+            name: "reused".to_owned(),
+            ..Default::default()
+        };
+        let bytes = tex()
+            .serialize("tex.png")
+            .unwrap()
+            .remove("tex.png")
+            .unwrap();
+        crate::Texture2D::deserialize_into(&bytes, &mut out).unwrap();
+        assert_eq!(out.data, tex().data);
+        assert_eq!(out.name, "reused");
+        let (ptr, capacity) = match &out.data {
+            crate::TextureData::RgbaU8(data) => (data.as_ptr(), data.capacity()),
+            _ => panic!("Wrong texture data: {:?}", out.data),
+        };
+
+        let mut other = tex();
+        other.data = crate::TextureData::RgbaU8(vec![[1, 2, 3, 4]; 4]);
+        let bytes = other
+            .serialize("tex.png")
+            .unwrap()
+            .remove("tex.png")
+            .unwrap();
+        crate::Texture2D::deserialize_into(&bytes, &mut out).unwrap();
+        assert_eq!(out.data, other.data);
+        match &out.data {
+            crate::TextureData::RgbaU8(data) => {
+                assert_eq!(data.as_ptr(), ptr);
+                assert_eq!(data.capacity(), capacity);
+            }
+            _ => panic!("Wrong texture data: {:?}", out.data),
+        }
+    }
+
+    #[test]
+    pub fn float_dynamic_image() {
+        use super::texture_data_from_dynamic_image;
+        use crate::TextureData;
+        use image::{DynamicImage, ImageBuffer};
+
+        let img = DynamicImage::ImageRgb32F(
+            ImageBuffer::from_raw(2, 1, vec![0.5, 1.0, 2.5, -1.0, 0.0, 100.0]).unwrap(),
+        );
+        assert_eq!(
+            texture_data_from_dynamic_image(img),
+            TextureData::RgbF32(vec![[0.5, 1.0, 2.5], [-1.0, 0.0, 100.0]])
+        );
+
+        let img = DynamicImage::ImageRgba32F(
+            ImageBuffer::from_raw(1, 1, vec![4.0, 0.25, 0.0, 0.5]).unwrap(),
+        );
+        assert_eq!(
+            texture_data_from_dynamic_image(img),
+            TextureData::RgbaF32(vec![[4.0, 0.25, 0.0, 0.5]])
+        );
+    }
+
+    #[cfg(all(feature = "png", feature = "data-url"))]
+    #[test]
+    pub fn data_url() {
+        use crate::Texture2D;
+        use image::ImageFormat;
+
+        let url = tex().to_data_url(ImageFormat::Png).unwrap();
+        assert!(url.starts_with("data:image/png;base64,iVBORw0KGgo"));
+        let decoded = Texture2D::from_data_url(&url).unwrap();
+        assert_eq!(decoded.data, tex().data);
+        assert_eq!((decoded.width, decoded.height), (tex().width, tex().height));
+
+        assert_eq!(super::base64_encode(b"ab"), "YWI=");
+        assert_eq!(super::base64_encode(b"abc"), "YWJj");
+        assert_eq!(super::base64_encode(b"abcd"), "YWJjZA==");
+
+        assert!(Texture2D::from_data_url("image/png;base64,iVBORw0KGgo").is_err());
+        assert!(Texture2D::from_data_url("data:image/png;base64,!!!").is_err());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn gltf_buffer_view() {
+        use crate::Texture2D;
+        use image::ImageFormat;
+
+        let mut png = Vec::new();
+        tex()
+            .write_to(std::io::Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let mut buffer = vec![7u8; 13];
+        buffer.extend_from_slice(&png);
+        buffer.extend_from_slice(&[0, 1, 2]);
+
+        let decoded =
+            Texture2D::from_gltf_buffer_view(&buffer, 13, png.len(), "image/png").unwrap();
+        assert_eq!(decoded.data, tex().data);
+        assert_eq!((decoded.width, decoded.height), (tex().width, tex().height));
+        assert_eq!(
+            Texture2D::from_gltf_buffer_view(&buffer, 13, png.len(), "")
+                .unwrap()
+                .data,
+            tex().data
+        );
+        assert!(matches!(
+            Texture2D::from_gltf_buffer_view(&buffer, 13, buffer.len(), "image/png"),
+            Err(crate::Error::InvalidBufferLength(..))
+        ));
+    }
+
+    #[test]
+    pub fn never_panics_on_invalid_input() {
+        use crate::Texture2D;
+
+        // A simple linear congruential generator, so the test is deterministic.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as u8
+        };
+        let mut inputs: Vec<(String, Vec<u8>)> = Vec::new();
+        for (i, length) in [0, 1, 7, 64, 1000].into_iter().enumerate() {
+            inputs.push((
+                format!("random{}.png", i),
+                (0..length).map(|_| random()).collect(),
+            ));
+        }
+        for file in [
+            "test.png", "test.jpg", "test.hdr", "test.tga", "test.gif", "test.bmp", "test.tif",
+        ] {
+            let bytes = std::fs::read(format!("test_data/{}", file)).unwrap();
+            // Truncated files.
+            for length in [0, 1, 8, 16, 32, 100, 500, bytes.len() / 2, bytes.len() - 1] {
+                inputs.push((file.to_owned(), bytes[..length.min(bytes.len())].to_vec()));
+            }
+            // Corrupted headers, keeping the magic bytes so the same decoder is used.
+            for _ in 0..10 {
+                let mut corrupted = bytes.clone();
+                for _ in 0..8 {
+                    let index = 4 + random() as usize % 60.min(corrupted.len() - 4);
+                    corrupted[index] = random();
+                }
+                inputs.push((file.to_owned(), corrupted));
+            }
+        }
+        for (name, bytes) in inputs {
+            let mut raw_assets = crate::io::RawAssets::new();
+            raw_assets.insert(&name, bytes);
+            // Must return an error or a texture but never panic.
+            let _ = raw_assets.deserialize::<Texture2D>(&name);
+        }
+        for length in [0, 1, 64] {
+            let bytes: Vec<u8> = (0..length).map(|_| random()).collect();
+            assert!(super::deserialize_img("random", &bytes).is_err());
+        }
+    }
+
+    #[cfg(feature = "jpeg")]
+    #[test]
+    pub fn decode_options() {
+        use super::{deserialize_img, deserialize_img_with_options, DecodeOptions};
+        use std::time::Duration;
+
+        let bytes = std::fs::read("test_data/test.jpg").unwrap();
+        let expected = deserialize_img("test.jpg", &bytes).unwrap();
+        let options = DecodeOptions {
+            max_decode_time: Some(Duration::from_secs(60)),
+        };
+        let tex = deserialize_img_with_options("test.jpg", &bytes, &options).unwrap();
+        assert_eq!(tex.data, expected.data);
+        assert!(matches!(
+            deserialize_img_with_options(
+                "test.jpg",
+                &bytes,
+                &DecodeOptions {
+                    max_decode_time: Some(Duration::ZERO),
+                }
+            ),
+            Err(crate::Error::DecodeTimeExceeded(..))
+        ));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn png_metadata() {
+        use super::PngMetadata;
+
+        let metadata = PngMetadata::default()
+            .with_text("Software", "three-d-asset")
+            .with_text("ColorSpace", "sRGB")
+            .with_text("Author", "Zoë");
+        let bytes = tex().to_png_bytes_with_metadata(&metadata).unwrap();
+
+        let mut reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        let info = reader.info();
+        let latin1: Vec<_> = info
+            .uncompressed_latin1_text
+            .iter()
+            .map(|c| (c.keyword.as_str(), c.text.as_str()))
+            .collect();
+        assert_eq!(
+            latin1,
+            vec![
+                ("Software", "three-d-asset"),
+                ("ColorSpace", "sRGB"),
+                ("Author", "Zoë")
+            ]
+        );
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut buffer).unwrap();
+        assert_eq!(
+            super::deserialize_img("test.png", &bytes).unwrap().data,
+            tex().data
+        );
+
+        let metadata = PngMetadata::default().with_text("Title", "テクスチャ");
+        let bytes = tex().to_png_bytes_with_metadata(&metadata).unwrap();
+        let mut reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut buffer).unwrap();
+        let chunk = &reader.info().utf8_text[0];
+        assert_eq!(chunk.keyword, "Title");
+        assert_eq!(chunk.get_text().unwrap(), "テクスチャ");
+
+        assert!(tex()
+            .to_png_bytes_with_metadata(&PngMetadata::default().with_text("", "empty"))
+            .is_err());
+
+        let tex16 = crate::Texture2D {
+            data: crate::TextureData::RU16(vec![1, 256, 4000, 65535]),
+            width: 2,
+            height: 2,
+            ..Default::default()
+        };
+        let bytes = tex16
+            .to_png_bytes_with_metadata(&PngMetadata::default())
+            .unwrap();
+        assert_eq!(
+            super::deserialize_img("test.png", &bytes).unwrap().data,
+            tex16.data
+        );
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn truncated() {
+        let bytes = std::fs::read("test_data/Cube_BaseColor.png").unwrap();
+        let half = &bytes[..bytes.len() / 2];
+        match super::deserialize_img("Cube_BaseColor.png", half) {
+            Err(crate::Error::Truncated(name, received)) => {
+                assert_eq!(name, "Cube_BaseColor.png");
+                assert_eq!(received, half.len());
+            }
+            _ => panic!("expected a truncation error"),
+        }
+
+        let mut raw_assets = crate::io::RawAssets::new();
+        raw_assets.insert("truncated.png", half.to_vec());
+        assert!(matches!(
+            raw_assets.deserialize::<crate::Texture2D>("truncated.png"),
+            Err(crate::Error::Truncated(..))
+        ));
+        // Bytes which are not an image at all are not reported as truncated.
+        assert!(!matches!(
+            super::deserialize_img("test.png", b"not an image"),
+            Err(crate::Error::Truncated(..))
+        ));
+    }
 }
@@ -0,0 +1,287 @@
+use crate::{io::RawAssets, texture::*, Error, Result};
+use std::path::Path;
+
+///
+/// Deserialize a paletted GIF or PNG image into an [IndexedTexture] without expanding the palette.
+/// Only the first frame of an animated GIF is used and the transparent color of a GIF is given an alpha value of zero.
+///
+pub fn deserialize_indexed(path: &Path, bytes: &[u8]) -> Result<IndexedTexture> {
+    let name = path.to_str().unwrap_or("").to_owned();
+    if bytes.starts_with(b"GIF8") {
+        #[cfg(not(feature = "gif"))]
+        return Err(Error::FeatureMissing("gif".to_string()));
+
+        #[cfg(feature = "gif")]
+        return deserialize_gif(&name, bytes);
+    }
+    if bytes.starts_with(b"\x89PNG") {
+        #[cfg(not(feature = "png"))]
+        return Err(Error::FeatureMissing("png".to_string()));
+
+        #[cfg(feature = "png")]
+        return deserialize_png(&name, bytes);
+    }
+    Err(Error::FailedDeserialize(name))
+}
+
+///
+/// Serialize the [IndexedTexture] as a paletted GIF or PNG image depending on the extension of the path.
+///
+pub fn serialize_indexed(texture: &IndexedTexture, path: &Path) -> Result<RawAssets> {
+    let name = path.to_str().unwrap_or("").to_owned();
+    let bytes = match path.extension().map(|e| e.to_str().unwrap()).unwrap_or("") {
+        "gif" => {
+            #[cfg(not(feature = "gif"))]
+            return Err(Error::FeatureMissing("gif".to_string()));
+
+            #[cfg(feature = "gif")]
+            serialize_gif(&name, texture)?
+        }
+        "png" => {
+            #[cfg(not(feature = "png"))]
+            return Err(Error::FeatureMissing("png".to_string()));
+
+            #[cfg(feature = "png")]
+            serialize_png(&name, texture)?
+        }
+        _ => return Err(Error::FailedSerialize(name)),
+    };
+    let mut raw_assets = RawAssets::new();
+    raw_assets.insert(path, bytes);
+    Ok(raw_assets)
+}
+
+#[cfg(feature = "gif")]
+fn deserialize_gif(name: &str, bytes: &[u8]) -> Result<IndexedTexture> {
+    let error = |_| Error::FailedDeserialize(name.to_owned());
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(bytes).map_err(error)?;
+    let (width, height) = (decoder.width() as usize, decoder.height() as usize);
+    let global_palette = decoder.global_palette().map(|p| p.to_vec());
+    let frame = decoder
+        .read_next_frame()
+        .map_err(error)?
+        .ok_or_else(|| Error::FailedDeserialize(name.to_owned()))?;
+    let palette = frame
+        .palette
+        .clone()
+        .or(global_palette)
+        .ok_or_else(|| Error::FailedConvertion("a palette".to_owned(), name.to_owned()))?;
+
+    // The first frame can be smaller than the image, in which case the rest is transparent.
+    let mut indices = vec![frame.transparent.unwrap_or(0); width * height];
+    let (left, top) = (frame.left as usize, frame.top as usize);
+    for (y, row) in frame.buffer.chunks(frame.width as usize).enumerate() {
+        for (x, index) in row.iter().enumerate() {
+            if left + x < width && top + y < height {
+                indices[(top + y) * width + left + x] = *index;
+            }
+        }
+    }
+    Ok(IndexedTexture {
+        palette: palette
+            .chunks(3)
+            .enumerate()
+            .map(|(i, c)| {
+                let alpha = if frame.transparent == Some(i as u8) {
+                    0
+                } else {
+                    255
+                };
+                [c[0], c[1], c[2], alpha]
+            })
+            .collect(),
+        indices,
+        width: width as u32,
+        height: height as u32,
+    })
+}
+
+#[cfg(feature = "gif")]
+fn serialize_gif(name: &str, texture: &IndexedTexture) -> Result<Vec<u8>> {
+    let width =
+        u16::try_from(texture.width).map_err(|_| Error::FailedSerialize(name.to_owned()))?;
+    let height =
+        u16::try_from(texture.height).map_err(|_| Error::FailedSerialize(name.to_owned()))?;
+    let pixel_count = width as usize * height as usize;
+    if texture.indices.len() != pixel_count {
+        Err(Error::InvalidBufferLength(
+            "indices".to_owned(),
+            pixel_count,
+            texture.indices.len(),
+        ))?;
+    }
+    if texture.palette.len() > 256 {
+        Err(Error::InvalidBufferLength(
+            "palette".to_owned(),
+            256,
+            texture.palette.len(),
+        ))?;
+    }
+    let palette: Vec<u8> = texture
+        .palette
+        .iter()
+        .flat_map(|c| [c[0], c[1], c[2]])
+        .collect();
+    let transparent = texture.palette.iter().position(|c| c[3] == 0);
+    let mut bytes = Vec::new();
+    {
+        let error = |_| Error::FailedSerialize(name.to_owned());
+        let mut encoder = gif::Encoder::new(&mut bytes, width, height, &palette).map_err(error)?;
+        let frame = gif::Frame::from_indexed_pixels(
+            width,
+            height,
+            texture.indices.clone(),
+            transparent.map(|i| i as u8),
+        );
+        encoder.write_frame(&frame).map_err(error)?;
+    }
+    Ok(bytes)
+}
+
+#[cfg(feature = "png")]
+fn deserialize_png(name: &str, bytes: &[u8]) -> Result<IndexedTexture> {
+    let error = |_| Error::FailedDeserialize(name.to_owned());
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::IDENTITY);
+    let mut reader = decoder.read_info().map_err(error)?;
+    let info = reader.info();
+    let (width, height) = (info.width as usize, info.height as usize);
+    let bit_depth = info.bit_depth as usize;
+    let palette = match (info.color_type, &info.palette) {
+        (png::ColorType::Indexed, Some(palette)) => {
+            let alphas = info.trns.as_deref().unwrap_or(&[]);
+            palette
+                .chunks(3)
+                .enumerate()
+                .map(|(i, c)| [c[0], c[1], c[2], alphas.get(i).copied().unwrap_or(255)])
+                .collect::<Vec<_>>()
+        }
+        _ => {
+            return Err(Error::FailedConvertion(
+                "a palette".to_owned(),
+                name.to_owned(),
+            ))
+        }
+    };
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let output = reader.next_frame(&mut buffer).map_err(error)?;
+
+    // Indices with a bit depth lower than 8 are packed into bytes with the leftmost pixel in the high-order bits.
+    let mask = ((1u16 << bit_depth) - 1) as u8;
+    let mut indices = Vec::with_capacity(width * height);
+    for row in buffer.chunks(output.line_size).take(height) {
+        for x in 0..width {
+            let bit = x * bit_depth;
+            indices.push((row[bit / 8] >> (8 - bit_depth - bit % 8)) & mask);
+        }
+    }
+    Ok(IndexedTexture {
+        palette,
+        indices,
+        width: width as u32,
+        height: height as u32,
+    })
+}
+
+#[cfg(feature = "png")]
+fn serialize_png(name: &str, texture: &IndexedTexture) -> Result<Vec<u8>> {
+    let error = |_| Error::FailedSerialize(name.to_owned());
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, texture.width, texture.height);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(
+            texture
+                .palette
+                .iter()
+                .flat_map(|c| [c[0], c[1], c[2]])
+                .collect::<Vec<_>>(),
+        );
+        if texture.palette.iter().any(|c| c[3] < 255) {
+            encoder.set_trns(texture.palette.iter().map(|c| c[3]).collect::<Vec<_>>());
+        }
+        let mut writer = encoder.write_header().map_err(error)?;
+        writer.write_image_data(&texture.indices).map_err(error)?;
+        writer.finish().map_err(error)?;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::{Deserialize, RawAssets, Serialize};
+    use crate::IndexedTexture;
+
+    fn indexed() -> IndexedTexture {
+        IndexedTexture {
+            palette: vec![
+                [255, 0, 0, 255],
+                [0, 255, 0, 255],
+                [0, 0, 255, 255],
+                [0, 0, 0, 0],
+            ],
+            indices: vec![0, 1, 2, 3, 3, 2, 1, 0],
+            width: 4,
+            height: 2,
+        }
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    pub fn paletted_gif() {
+        let palette = [255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0];
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut bytes, 4, 2, &palette).unwrap();
+            encoder
+                .write_frame(&gif::Frame::from_indexed_pixels(
+                    4,
+                    2,
+                    vec![0, 1, 2, 3, 3, 2, 1, 0],
+                    Some(3),
+                ))
+                .unwrap();
+        }
+        let mut raw_assets = RawAssets::new();
+        raw_assets.insert("paletted.gif", bytes);
+        let texture: IndexedTexture = raw_assets.deserialize("paletted.gif").unwrap();
+        assert_eq!(texture, indexed());
+
+        let mut raw_assets = texture.serialize("paletted.gif").unwrap();
+        assert_eq!(
+            IndexedTexture::deserialize("paletted.gif", &mut raw_assets).unwrap(),
+            indexed()
+        );
+
+        let mut texture = indexed();
+        texture.indices.pop();
+        assert!(matches!(
+            texture.serialize("paletted.gif"),
+            Err(crate::Error::InvalidBufferLength(_, 8, 7))
+        ));
+        let mut texture = indexed();
+        texture.palette = vec![[0; 4]; 257];
+        assert!(matches!(
+            texture.serialize("paletted.gif"),
+            Err(crate::Error::InvalidBufferLength(_, 256, 257))
+        ));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn paletted_png() {
+        let mut raw_assets = indexed().serialize("paletted.png").unwrap();
+        let texture: IndexedTexture = raw_assets.deserialize("paletted.png").unwrap();
+        assert_eq!(texture, indexed());
+
+        let mut raw_assets = RawAssets::new();
+        raw_assets.insert(
+            "rgb.png",
+            std::fs::read("test_data/Cube_BaseColor.png").unwrap(),
+        );
+        assert!(raw_assets.deserialize::<IndexedTexture>("rgb.png").is_err());
+    }
+}
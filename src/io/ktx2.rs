@@ -0,0 +1,311 @@
+use crate::{texture::*, Error, Result};
+
+pub(crate) const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+const HEADER_SIZE: usize = 80;
+const LEVEL_INDEX_ENTRY_SIZE: usize = 24;
+
+impl Texture2D {
+    ///
+    /// Serializes this texture into an uncompressed KTX2 file, which can be uploaded to the GPU without any conversion.
+    /// The Vulkan format matches the [TextureData] variant, where 8-bit RGB and RGBA data is stored in an sRGB format
+    /// (see [TextureDescriptor::is_srgb_candidate]) and the other integer formats in an unsigned normalized format.
+    ///
+    /// If [Texture2D::mip_map_filter] is set and mipmaps are [supported](Texture2D::mipmaps_supported) for this texture,
+    /// the file also contains the mip chain generated by [Texture2D::generate_mipmaps] with a [DownsampleKernel::Box] filter.
+    ///
+    pub fn to_ktx2_bytes(&self) -> Result<Vec<u8>> {
+        if self.data.len() != self.width as usize * self.height as usize {
+            return Err(Error::FailedSerialize(self.name.clone()));
+        }
+        let descriptor = self.data.descriptor();
+        let type_size = descriptor.bits_per_channel / 8;
+        let texel_size = (descriptor.channels * type_size) as usize;
+        let mut levels = vec![texel_bytes(&self.data)];
+        if self.mip_map_filter.is_some() && self.mipmaps_supported() {
+            for level in self.generate_mipmaps(DownsampleKernel::Box)? {
+                levels.push(texel_bytes(&level.data));
+            }
+        }
+        let dfd = data_format_descriptor(&descriptor, self.premultiplied);
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&IDENTIFIER);
+        for value in [
+            vk_format(&descriptor),
+            type_size,
+            self.width,
+            self.height,
+            0, // pixel depth
+            0, // layer count
+            1, // face count
+            levels.len() as u32,
+            0, // supercompression scheme
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        let dfd_offset = HEADER_SIZE + LEVEL_INDEX_ENTRY_SIZE * levels.len();
+        for value in [dfd_offset as u32, dfd.len() as u32, 0, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0; 16]); // supercompression global data
+
+        // The levels are stored from the smallest to the largest, each aligned to the least common multiple of the texel size and 4.
+        let alignment = texel_size * 4 / gcd(texel_size, 4);
+        let mut offsets = vec![0; levels.len()];
+        let mut offset = dfd_offset + dfd.len();
+        for (i, level) in levels.iter().enumerate().rev() {
+            offset = offset.div_ceil(alignment) * alignment;
+            offsets[i] = offset;
+            offset += level.len();
+        }
+        for (level, offset) in levels.iter().zip(offsets.iter()) {
+            for value in [*offset, level.len(), level.len()] {
+                bytes.extend_from_slice(&(value as u64).to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(&dfd);
+        for (level, offset) in levels.iter().zip(offsets.iter()).rev() {
+            bytes.resize(*offset, 0);
+            bytes.extend_from_slice(level);
+        }
+        Ok(bytes)
+    }
+
+    ///
+    /// Deserializes the base level of an uncompressed KTX2 file with one of the formats written by [Texture2D::to_ktx2_bytes].
+    ///
+    /// Returns a [Ktx2CorruptData](crate::Error::Ktx2CorruptData) error if the file is not a valid KTX2 file or if it contains
+    /// a compressed, supercompressed, array, cube map or 3D texture.
+    ///
+    pub fn from_ktx2_bytes(bytes: &[u8]) -> Result<Texture2D> {
+        let u32_at = |offset: usize| {
+            bytes
+                .get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or(Error::Ktx2CorruptData)
+        };
+        let u64_at = |offset: usize| -> Result<u64> {
+            Ok(u32_at(offset)? as u64 | (u32_at(offset + 4)? as u64) << 32)
+        };
+        if !bytes.starts_with(&IDENTIFIER) {
+            return Err(Error::Ktx2CorruptData);
+        }
+        let (vk_format, width, height) = (u32_at(12)?, u32_at(20)?, u32_at(24)?);
+        let (depth, layers, faces, supercompression) =
+            (u32_at(28)?, u32_at(32)?, u32_at(36)?, u32_at(44)?);
+        if depth > 1 || layers > 1 || faces != 1 || supercompression != 0 {
+            return Err(Error::Ktx2CorruptData);
+        }
+        let premultiplied = u32_at(u32_at(48)? as usize + 12).is_ok_and(|v| v >> 24 & 1 == 1);
+        let offset = u64_at(HEADER_SIZE)? as usize;
+        let length = u64_at(HEADER_SIZE + 8)? as usize;
+        let level = offset
+            .checked_add(length)
+            .and_then(|end| bytes.get(offset..end))
+            .ok_or(Error::Ktx2CorruptData)?;
+        let data = texture_data_from_bytes(vk_format, level)?;
+        if data.len() != width as usize * height as usize {
+            return Err(Error::Ktx2CorruptData);
+        }
+        Ok(Texture2D {
+            data,
+            width,
+            height,
+            premultiplied,
+            ..Default::default()
+        })
+    }
+}
+
+fn vk_format(descriptor: &TextureDescriptor) -> u32 {
+    match (
+        descriptor.channels,
+        descriptor.bits_per_channel,
+        descriptor.is_float,
+        descriptor.is_srgb_candidate,
+    ) {
+        (1, 8, _, _) => 9,       // VK_FORMAT_R8_UNORM
+        (2, 8, _, _) => 16,      // VK_FORMAT_R8G8_UNORM
+        (3, 8, _, true) => 29,   // VK_FORMAT_R8G8B8_SRGB
+        (3, 8, _, false) => 23,  // VK_FORMAT_R8G8B8_UNORM
+        (4, 8, _, true) => 43,   // VK_FORMAT_R8G8B8A8_SRGB
+        (4, 8, _, false) => 37,  // VK_FORMAT_R8G8B8A8_UNORM
+        (1, 16, false, _) => 70, // VK_FORMAT_R16_UNORM
+        (2, 16, false, _) => 77, // VK_FORMAT_R16G16_UNORM
+        (3, 16, false, _) => 84, // VK_FORMAT_R16G16B16_UNORM
+        (4, 16, false, _) => 91, // VK_FORMAT_R16G16B16A16_UNORM
+        (1, 16, true, _) => 76,  // VK_FORMAT_R16_SFLOAT
+        (2, 16, true, _) => 83,  // VK_FORMAT_R16G16_SFLOAT
+        (3, 16, true, _) => 90,  // VK_FORMAT_R16G16B16_SFLOAT
+        (4, 16, true, _) => 97,  // VK_FORMAT_R16G16B16A16_SFLOAT
+        (1, _, _, _) => 100,     // VK_FORMAT_R32_SFLOAT
+        (2, _, _, _) => 103,     // VK_FORMAT_R32G32_SFLOAT
+        (3, _, _, _) => 106,     // VK_FORMAT_R32G32B32_SFLOAT
+        _ => 109,                // VK_FORMAT_R32G32B32A32_SFLOAT
+    }
+}
+
+///
+/// Returns the data format descriptor, including the total size, with a basic descriptor block describing the layout of each channel.
+///
+fn data_format_descriptor(descriptor: &TextureDescriptor, premultiplied: bool) -> Vec<u8> {
+    let channels = descriptor.channels as usize;
+    let bits = descriptor.bits_per_channel;
+    let block_size = 24 + 16 * channels;
+    let mut dfd = Vec::with_capacity(4 + block_size);
+    dfd.extend_from_slice(&(4 + block_size as u32).to_le_bytes());
+    dfd.extend_from_slice(&0u32.to_le_bytes()); // Khronos vendor and basic descriptor type
+    dfd.extend_from_slice(&2u16.to_le_bytes()); // version
+    dfd.extend_from_slice(&(block_size as u16).to_le_bytes());
+    dfd.extend_from_slice(&[
+        1, // RGBSDA color model
+        1, // BT.709 color primaries
+        if descriptor.is_srgb_candidate { 2 } else { 1 },
+        premultiplied as u8,
+    ]);
+    dfd.extend_from_slice(&[0; 4]); // texel block dimensions of 1x1x1x1
+    dfd.extend_from_slice(&[(channels as u32 * bits / 8) as u8, 0, 0, 0, 0, 0, 0, 0]);
+    for i in 0..channels {
+        let id = if i == 3 { 15 } else { i as u8 };
+        let (channel_type, lower, upper) = if descriptor.is_float {
+            (id | 0xC0, (-1.0f32).to_bits(), 1.0f32.to_bits())
+        } else if id == 15 && descriptor.is_srgb_candidate {
+            (id | 0x10, 0, (1u32 << bits) - 1)
+        } else {
+            (id, 0, (1u32 << bits) - 1)
+        };
+        dfd.extend_from_slice(&((i as u32 * bits) as u16).to_le_bytes());
+        dfd.extend_from_slice(&[(bits - 1) as u8, channel_type, 0, 0, 0, 0]);
+        dfd.extend_from_slice(&lower.to_le_bytes());
+        dfd.extend_from_slice(&upper.to_le_bytes());
+    }
+    dfd
+}
+
+fn texel_bytes(data: &TextureData) -> Vec<u8> {
+    macro_rules! bytes {
+        ($values:expr, $to_bytes:expr) => {
+            $values
+                .iter()
+                .flat_map(|texel| texel.iter().flat_map($to_bytes))
+                .collect()
+        };
+    }
+    match data {
+        TextureData::RU8(values) => values.clone(),
+        TextureData::RgU8(values) => values.iter().flatten().copied().collect(),
+        TextureData::RgbU8(values) => values.iter().flatten().copied().collect(),
+        TextureData::RgbaU8(values) => values.iter().flatten().copied().collect(),
+        TextureData::RU16(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        TextureData::RgU16(values) => bytes!(values, |v: &u16| v.to_le_bytes()),
+        TextureData::RgbU16(values) => bytes!(values, |v: &u16| v.to_le_bytes()),
+        TextureData::RgbaU16(values) => bytes!(values, |v: &u16| v.to_le_bytes()),
+        TextureData::RF16(values) => values
+            .iter()
+            .flat_map(|v| v.to_bits().to_le_bytes())
+            .collect(),
+        TextureData::RgF16(values) => bytes!(values, |v: &f16| v.to_bits().to_le_bytes()),
+        TextureData::RgbF16(values) => bytes!(values, |v: &f16| v.to_bits().to_le_bytes()),
+        TextureData::RgbaF16(values) => bytes!(values, |v: &f16| v.to_bits().to_le_bytes()),
+        TextureData::RF32(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        TextureData::RgF32(values) => bytes!(values, |v: &f32| v.to_le_bytes()),
+        TextureData::RgbF32(values) => bytes!(values, |v: &f32| v.to_le_bytes()),
+        TextureData::RgbaF32(values) => bytes!(values, |v: &f32| v.to_le_bytes()),
+    }
+}
+
+fn texture_data_from_bytes(vk_format: u32, bytes: &[u8]) -> Result<TextureData> {
+    let format = match vk_format {
+        9 | 15 => TextureFormat::R8,
+        16 | 22 => TextureFormat::Rg8,
+        23 | 29 => TextureFormat::Rgb8,
+        37 | 43 => TextureFormat::Rgba8,
+        70 => TextureFormat::R16,
+        77 => TextureFormat::Rg16,
+        84 => TextureFormat::Rgb16,
+        91 => TextureFormat::Rgba16,
+        76 => TextureFormat::R16F,
+        83 => TextureFormat::Rg16F,
+        90 => TextureFormat::Rgb16F,
+        97 => TextureFormat::Rgba16F,
+        100 => TextureFormat::R32F,
+        103 => TextureFormat::Rg32F,
+        106 => TextureFormat::Rgb32F,
+        109 => TextureFormat::Rgba32F,
+        _ => return Err(Error::Ktx2CorruptData),
+    };
+    Ok(TextureData::from_bytes(format, bytes, Endianness::Little))
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::{Deserialize, Serialize};
+
+    #[test]
+    pub fn round_trip() {
+        let tex = Texture2D::from_fn(4, 4, |x, y| [x as u8 * 60, y as u8 * 60, 10, 200]);
+        let bytes = tex.to_ktx2_bytes().unwrap();
+        assert!(bytes.starts_with(&IDENTIFIER));
+        assert_eq!(u32::from_le_bytes(bytes[12..16].try_into().unwrap()), 43);
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()), 3);
+        let loaded = Texture2D::from_ktx2_bytes(&bytes).unwrap();
+        assert_eq!(loaded.data, tex.data);
+        assert_eq!((loaded.width, loaded.height), (4, 4));
+
+        let tex = Texture2D {
+            data: TextureData::RgbF32(vec![[0.5, -1.0, 100.0]; 6]),
+            width: 3,
+            height: 2,
+            ..Default::default()
+        };
+        let bytes = tex.to_ktx2_bytes().unwrap();
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()), 1);
+        let offset = u64::from_le_bytes(bytes[80..88].try_into().unwrap());
+        assert_eq!(offset % 12, 0);
+        assert_eq!(Texture2D::from_ktx2_bytes(&bytes).unwrap().data, tex.data);
+
+        let tex = Texture2D {
+            data: TextureData::RgF16(vec![[f16::from_f32(0.25), f16::from_f32(-2.0)]; 4]),
+            width: 2,
+            height: 2,
+            premultiplied: true,
+            ..Default::default()
+        };
+        let mut raw_assets = tex.serialize("tex.ktx2").unwrap();
+        let loaded = Texture2D::deserialize("tex.ktx2", &mut raw_assets).unwrap();
+        assert_eq!(loaded.data, tex.data);
+        assert!(loaded.premultiplied);
+
+        assert!(Texture2D::from_ktx2_bytes(&bytes[..60]).is_err());
+        assert!(Texture2D::from_ktx2_bytes(b"not a ktx2 file").is_err());
+
+        // A level index with an offset and length which overflow when added.
+        let mut corrupt = bytes.clone();
+        corrupt[80..88].copy_from_slice(&u64::MAX.to_le_bytes());
+        corrupt[88..96].copy_from_slice(&16u64.to_le_bytes());
+        assert!(matches!(
+            Texture2D::from_ktx2_bytes(&corrupt),
+            Err(crate::Error::Ktx2CorruptData)
+        ));
+
+        use crate::io::Asset;
+        let bytes = tex.to_ktx2_bytes().unwrap();
+        assert!(Texture2D::supported_extensions().contains(&"ktx2"));
+        assert_eq!(Texture2D::load(&bytes).unwrap().data, tex.data);
+        assert_eq!(
+            Texture2D::from_path_bytes("tex.ktx2", &bytes).unwrap().data,
+            tex.data
+        );
+    }
+}
@@ -3,8 +3,10 @@
 //!
 
 use crate::{io::RawAssets, Error, Result};
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
 
 ///
 /// Loads all of the resources in the given paths and returns the [RawAssets] resources.
@@ -27,6 +29,149 @@ pub fn load(paths: &[impl AsRef<Path>]) -> Result<RawAssets> {
     Ok(raw_assets)
 }
 
+///
+/// Memory-maps the file at the given path read-only instead of reading it into memory, which avoids copying multi-gigabyte textures or volumes.
+/// The returned [MappedFile] dereferences to the bytes of the file, which can be passed directly to a decoder,
+/// for example [deserialize_img_with_options](crate::io::deserialize_img_with_options).
+/// Dependencies are not loaded, use [load] for assets like glTF files which refer to other files.
+///
+/// The file must not be modified while it is mapped, since that is visible through the mapped bytes.
+///
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+pub fn load_mmap(path: impl AsRef<Path>) -> Result<MappedFile> {
+    let path = path.as_ref();
+    let error = |e| Error::FailedLoading(path.to_string_lossy().to_string(), e);
+    let file = std::fs::File::open(path).map_err(error)?;
+    // Safety: the mapping is read-only and the documentation requires that the file is not modified while it is mapped.
+    let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(error)?;
+    Ok(MappedFile {
+        path: path.to_path_buf(),
+        mmap,
+    })
+}
+
+///
+/// A read-only memory-mapped file returned by [load_mmap], which dereferences to the bytes of the file.
+///
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+#[derive(Debug)]
+pub struct MappedFile {
+    path: PathBuf,
+    mmap: memmap2::Mmap,
+}
+
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+impl MappedFile {
+    ///
+    /// Returns the path of the mapped file.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+impl std::ops::Deref for MappedFile {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.mmap
+    }
+}
+
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+impl AsRef<[u8]> for MappedFile {
+    fn as_ref(&self) -> &[u8] {
+        &self.mmap
+    }
+}
+
+///
+/// Loads all of the files in the given zip archive and returns the [RawAssets] resources.
+/// The files are indexed by their path inside the archive, so for example a texture at `folder/tex.png` in the archive
+/// can be deserialized using `raw_assets.deserialize("folder/tex.png")`.
+/// Directories are skipped and dependencies are expected to be part of the archive.
+///
+#[cfg(feature = "zip")]
+pub fn load_zip(archive_bytes: &[u8]) -> Result<RawAssets> {
+    use std::io::Read;
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(archive_bytes))?;
+    let mut raw_assets = RawAssets::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_file() {
+            let mut bytes = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut bytes)
+                .map_err(zip::result::ZipError::from)?;
+            raw_assets.insert(file.name(), bytes);
+        }
+    }
+    Ok(raw_assets)
+}
+
+///
+/// Loads all of the assets listed in the manifest file at the given path and returns the [RawAssets] resources.
+/// The manifest is either a JSON (`.json`) or a RON (`.ron`) file containing a map from keys to asset paths,
+/// for example `{ "grass": "textures/grass.png", "rock": "textures/rock.png" }`, or a list of asset paths, in which case the paths are also the keys.
+/// The asset paths are relative to the directory of the manifest.
+///
+/// Each asset is stored under its key with the extension of the asset path appended, unless the key already has that extension,
+/// so the texture above is deserialized using `raw_assets.deserialize("grass.png")`.
+/// Dependencies of the assets, like the buffers of a glTF file, are loaded as well and stored under their paths.
+///
+#[cfg(all(feature = "manifest", not(target_arch = "wasm32")))]
+pub fn load_manifest(path: impl AsRef<Path>) -> Result<RawAssets> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)
+        .map_err(|e| Error::FailedLoading(path.to_str().unwrap().to_string(), e))?;
+    let entries = parse_manifest(path, &bytes)?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let paths = entries
+        .iter()
+        .map(|(_, p)| directory.join(p))
+        .collect::<Vec<_>>();
+    let mut raw_assets = load(&paths)?;
+    let assets = paths
+        .iter()
+        .map(|p| raw_assets.get(p).map(|bytes| bytes.to_vec()))
+        .collect::<Result<Vec<_>>>()?;
+    for path in paths.iter() {
+        raw_assets.remove(path).ok();
+    }
+    for ((key, asset_path), bytes) in entries.iter().zip(assets) {
+        let key = match Path::new(asset_path).extension() {
+            Some(extension) if Path::new(key).extension() != Some(extension) => {
+                format!("{}.{}", key, extension.to_str().unwrap())
+            }
+            _ => key.clone(),
+        };
+        raw_assets.insert(key, bytes);
+    }
+    Ok(raw_assets)
+}
+
+#[cfg(all(feature = "manifest", not(target_arch = "wasm32")))]
+fn parse_manifest(path: &Path, bytes: &[u8]) -> Result<Vec<(String, String)>> {
+    use std::collections::BTreeMap;
+    let error = || Error::FailedDeserialize(path.to_str().unwrap().to_string());
+    let entries: BTreeMap<String, String> =
+        match path.extension().map(|e| e.to_str().unwrap()).unwrap_or("") {
+            "json" => serde_json::from_slice(bytes)
+                .or_else(|_| {
+                    serde_json::from_slice::<Vec<String>>(bytes)
+                        .map(|paths| paths.into_iter().map(|p| (p.clone(), p)).collect())
+                })
+                .map_err(|_| error())?,
+            "ron" => ron::de::from_bytes(bytes)
+                .or_else(|_| {
+                    ron::de::from_bytes::<Vec<String>>(bytes)
+                        .map(|paths| paths.into_iter().map(|p| (p.clone(), p)).collect())
+                })
+                .map_err(|_| error())?,
+            _ => return Err(error()),
+        };
+    Ok(entries.into_iter().collect())
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn load_single(paths: &[impl AsRef<Path>]) -> Result<RawAssets> {
     let mut data_urls = HashSet::new();
@@ -64,6 +209,159 @@ pub async fn load_async(paths: &[impl AsRef<Path>]) -> Result<RawAssets> {
     Ok(raw_assets)
 }
 
+///
+/// Starts loading each of the given paths, including their dependencies, as a separate load and returns a [LoadHandle]
+/// which yields the results as they complete and which can cancel the loads that are no longer needed.
+///
+/// On native, the loads run on a pool of background threads and support the same functionality as [load].
+/// On web, the loads are downloaded one at a time while awaiting [LoadHandle::next] and support the same functionality as [load_async].
+///
+pub fn load_batch(paths: &[impl AsRef<Path>]) -> LoadHandle {
+    let mut queue = VecDeque::new();
+    let mut outstanding = HashSet::new();
+    for path in paths.iter() {
+        let path = path.as_ref().to_path_buf();
+        if outstanding.insert(path.clone()) {
+            queue.push_back(path);
+        }
+    }
+    let handle = LoadHandle {
+        state: Arc::new(Mutex::new(LoadBatchState {
+            queue,
+            outstanding,
+            results: VecDeque::new(),
+            waker: None,
+        })),
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+            .min(paths.len());
+        for _ in 0..workers {
+            let state = handle.state.clone();
+            std::thread::spawn(move || loop {
+                let path = match state.lock().unwrap().queue.pop_front() {
+                    Some(path) => path,
+                    None => break,
+                };
+                let result = load(&[&path]);
+                let mut state = state.lock().unwrap();
+                if state.outstanding.contains(&path) {
+                    state.results.push_back((path, result));
+                    if let Some(waker) = state.waker.take() {
+                        waker.wake();
+                    }
+                }
+            });
+        }
+    }
+    handle
+}
+
+///
+/// A handle to a batch of loads started with [load_batch], which yields the result of each load as it completes.
+/// A load which is canceled before its result is returned by [LoadHandle::next] never delivers a result.
+/// Dropping the handle cancels all loads which are not delivered yet.
+///
+pub struct LoadHandle {
+    state: Arc<Mutex<LoadBatchState>>,
+}
+
+impl Drop for LoadHandle {
+    ///
+    /// Cancels all loads which are not delivered yet, so that the background threads stop after their current load.
+    ///
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+struct LoadBatchState {
+    /// The paths which have not started loading yet.
+    queue: VecDeque<PathBuf>,
+    /// The paths which are neither delivered nor canceled.
+    outstanding: HashSet<PathBuf>,
+    /// The completed loads which are not delivered yet.
+    results: VecDeque<(PathBuf, Result<RawAssets>)>,
+    waker: Option<Waker>,
+}
+
+impl LoadHandle {
+    ///
+    /// Returns the path and the result of the next load to complete or `None` when all loads are either delivered or canceled.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn next(&mut self) -> Option<(PathBuf, Result<RawAssets>)> {
+        use std::task::Poll;
+        std::future::poll_fn(|context| {
+            let mut state = self.state.lock().unwrap();
+            if let Some((path, result)) = state.results.pop_front() {
+                state.outstanding.remove(&path);
+                Poll::Ready(Some((path, result)))
+            } else if state.outstanding.is_empty() {
+                Poll::Ready(None)
+            } else {
+                state.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    ///
+    /// Returns the path and the result of the next load to complete or `None` when all loads are either delivered or canceled.
+    ///
+    #[cfg(target_arch = "wasm32")]
+    pub async fn next(&mut self) -> Option<(PathBuf, Result<RawAssets>)> {
+        loop {
+            let path = self.state.lock().unwrap().queue.pop_front()?;
+            let result = load_async(&[&path]).await;
+            if self.state.lock().unwrap().outstanding.remove(&path) {
+                return Some((path, result));
+            }
+        }
+    }
+
+    ///
+    /// Cancels the load of the given path if it is not delivered yet.
+    /// A load which is already running is not interrupted, but its result is discarded.
+    ///
+    pub fn cancel_path(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let mut state = self.state.lock().unwrap();
+        state.outstanding.remove(path);
+        state.queue.retain(|p| p != path);
+        state.results.retain(|(p, _)| p != path);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    ///
+    /// Cancels all loads which are not delivered yet, after which [LoadHandle::next] returns `None`.
+    ///
+    pub fn cancel(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.outstanding.clear();
+        state.queue.clear();
+        state.results.clear();
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    ///
+    /// Returns the number of loads which are neither delivered nor canceled.
+    ///
+    pub fn pending(&self) -> usize {
+        self.state.lock().unwrap().outstanding.len()
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 async fn load_async_single(paths: &[impl AsRef<Path>]) -> Result<RawAssets> {
     let base_path = base_path();
@@ -140,10 +438,16 @@ async fn load_urls(paths: HashSet<PathBuf>, raw_assets: &mut RawAssets) -> Resul
             handles.push((path, client.get(url).send().await));
         }
         for (path, handle) in handles.drain(..) {
-            let bytes = handle
-                .map_err(|e| {
-                    Error::FailedLoadingUrlWithReqwest(path.to_str().unwrap().to_string(), e)
-                })?
+            let response = handle.map_err(|e| {
+                Error::FailedLoadingUrlWithReqwest(path.to_str().unwrap().to_string(), e)
+            })?;
+            if !response.status().is_success() {
+                Err(Error::FailedLoadingUrlWithStatusCode(
+                    path.to_str().unwrap().to_string(),
+                    response.status().as_u16(),
+                ))?;
+            }
+            let bytes = response
                 .bytes()
                 .await
                 .map_err(|e| {
@@ -242,4 +546,116 @@ mod test {
 
         assert_eq!(loaded_data_url, loaded_image);
     }
+
+    #[cfg(all(feature = "zip", feature = "png"))]
+    #[test]
+    pub fn load_zip() {
+        use std::io::Write;
+        let png_bytes = include_bytes!("../../test_data/test.png");
+        let mut archive_bytes = Vec::new();
+        {
+            let mut writer = zip::ZipWriter::new(std::io::Cursor::new(&mut archive_bytes));
+            writer
+                .add_directory("folder", zip::write::FileOptions::default())
+                .unwrap();
+            writer
+                .start_file("folder/tex.png", zip::write::FileOptions::default())
+                .unwrap();
+            writer.write_all(png_bytes).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let mut raw_assets = super::load_zip(&archive_bytes).unwrap();
+        assert_eq!(raw_assets.len(), 1);
+        assert_eq!(raw_assets.get("folder/tex.png").unwrap(), png_bytes);
+        let tex: crate::Texture2D = raw_assets.deserialize("folder/tex.png").unwrap();
+        assert_eq!(tex.width, 2);
+        assert_eq!(tex.height, 2);
+    }
+
+    #[cfg(all(feature = "manifest", feature = "png", feature = "jpeg"))]
+    #[test]
+    pub fn load_manifest() {
+        use crate::Texture2D;
+        let mut raw_assets = super::load_manifest("test_data/manifest.json").unwrap();
+        assert_eq!(raw_assets.len(), 2);
+        let base_color: Texture2D = raw_assets.deserialize("base_color.png").unwrap();
+        assert_eq!((base_color.width, base_color.height), (512, 512));
+        let metallic_roughness: Texture2D =
+            raw_assets.deserialize("metallic_roughness.png").unwrap();
+        assert_eq!(metallic_roughness.width, 512);
+
+        let mut raw_assets = super::load_manifest("test_data/manifest.ron").unwrap();
+        assert_eq!(raw_assets.len(), 2);
+        raw_assets
+            .deserialize::<Texture2D>("Cube_BaseColor.png")
+            .unwrap();
+        raw_assets.deserialize::<Texture2D>("test.jpg").unwrap();
+
+        assert!(super::load_manifest("test_data/test.png").is_err());
+    }
+
+    #[tokio::test]
+    pub async fn load_batch() {
+        let mut handle = super::load_batch(&[
+            "test_data/Cube_BaseColor.png",
+            "test_data/test.jpg",
+            "test_data/missing.png",
+        ]);
+        assert_eq!(handle.pending(), 3);
+        handle.cancel_path("test_data/test.jpg");
+        assert_eq!(handle.pending(), 2);
+
+        let mut delivered = Vec::new();
+        while let Some((path, result)) = handle.next().await {
+            assert_eq!(result.is_ok(), path.ends_with("Cube_BaseColor.png"));
+            delivered.push(path);
+        }
+        delivered.sort();
+        assert_eq!(
+            delivered,
+            vec![
+                std::path::PathBuf::from("test_data/Cube_BaseColor.png"),
+                std::path::PathBuf::from("test_data/missing.png")
+            ]
+        );
+
+        let mut handle = super::load_batch(&["test_data/Cube_BaseColor.png"]);
+        handle.cancel();
+        assert!(handle.next().await.is_none());
+
+        // Dropping the handle cancels the remaining loads.
+        let paths: Vec<_> = (0..100)
+            .map(|i| format!("test_data/missing{}.png", i))
+            .collect();
+        let handle = super::load_batch(&paths);
+        let state = handle.state.clone();
+        drop(handle);
+        let state = state.lock().unwrap();
+        assert!(state.queue.is_empty());
+        assert!(state.outstanding.is_empty());
+    }
+
+    #[cfg(all(feature = "mmap", feature = "png"))]
+    #[test]
+    pub fn load_mmap() {
+        let mapped = super::load_mmap("test_data/Cube_BaseColor.png").unwrap();
+        assert_eq!(
+            mapped.path(),
+            std::path::Path::new("test_data/Cube_BaseColor.png")
+        );
+        assert_eq!(
+            &mapped[..],
+            std::fs::read("test_data/Cube_BaseColor.png").unwrap()
+        );
+        let tex =
+            crate::io::deserialize_img_with_options(mapped.path(), &mapped, &Default::default())
+                .unwrap();
+        assert_eq!((tex.width, tex.height), (512, 512));
+
+        assert!(matches!(
+            super::load_mmap("test_data/missing.png"),
+            Err(crate::Error::FailedLoading(..))
+        ));
+    }
 }
@@ -0,0 +1,300 @@
+use crate::{texture::*, Error, Result};
+
+///
+/// The maximum width and height of a .psd file. Larger images must be stored as .psb files, which are not supported.
+///
+const MAX_DIMENSION: u32 = 30000;
+
+///
+/// Deserialize the given bytes of a .psd (Photoshop) file into a [Texture2D] with [TextureData::RgbaU8] data.
+/// The flattened composite image stored in the file is returned, which means the file must be saved with maximized compatibility
+/// (the default in Photoshop) for the result to contain all layers.
+///
+/// **Note:** The file is parsed by this crate and only a subset of the format is supported:
+/// - .psd files (version 1) with a width and height of at most 30000 pixels, not large document .psb files (version 2),
+/// - a depth of 8 bits per channel, not 1, 16 or 32 bits,
+/// - the grayscale and RGB color modes, not bitmap, indexed, CMYK, multichannel, duotone or Lab,
+/// - raw or RLE compressed image data.
+///
+/// Other files return a [PsdCorruptData](crate::Error::PsdCorruptData) error. Individual layers are not decoded.
+///
+pub fn psd_from_bytes(bytes: &[u8]) -> Result<Texture2D> {
+    let mut reader = Reader { bytes, position: 0 };
+    if reader.take(4)? != b"8BPS" || reader.u16()? != 1 {
+        Err(Error::PsdCorruptData)?;
+    }
+    reader.take(6)?;
+    let channels = reader.u16()? as usize;
+    let height = reader.u32()?;
+    let width = reader.u32()?;
+    let depth = reader.u16()?;
+    let color_mode = reader.u16()?;
+    if depth != 8
+        || !(color_mode == 1 || color_mode == 3)
+        || width > MAX_DIMENSION
+        || height > MAX_DIMENSION
+    {
+        Err(Error::PsdCorruptData)?;
+    }
+    // Skip the color mode data, image resources and layer and mask information sections.
+    for _ in 0..3 {
+        let length = reader.u32()? as usize;
+        reader.take(length)?;
+    }
+
+    let pixel_count = width as usize * height as usize;
+    let planes = match reader.u16()? {
+        0 => (0..channels)
+            .map(|_| reader.take(pixel_count).map(|p| p.to_vec()))
+            .collect::<Result<Vec<_>>>()?,
+        1 => {
+            let row_lengths = (0..channels * height as usize)
+                .map(|_| reader.u16().map(|l| l as usize))
+                .collect::<Result<Vec<_>>>()?;
+            // The planes are not preallocated since the dimensions are not yet verified against the data.
+            let mut planes = vec![Vec::new(); channels];
+            for (i, row_length) in row_lengths.into_iter().enumerate() {
+                unpack_bits(reader.take(row_length)?, &mut planes[i / height as usize])?;
+            }
+            planes
+        }
+        _ => Err(Error::PsdCorruptData)?,
+    };
+    if planes.len() < if color_mode == 3 { 3 } else { 1 }
+        || planes.iter().any(|p| p.len() != pixel_count)
+    {
+        Err(Error::PsdCorruptData)?;
+    }
+
+    let (color_channels, alpha) = if color_mode == 3 { (3, 3) } else { (1, 1) };
+    let data = (0..pixel_count)
+        .map(|i| {
+            let a = planes.get(alpha).map(|p| p[i]).unwrap_or(255);
+            if color_channels == 3 {
+                [planes[0][i], planes[1][i], planes[2][i], a]
+            } else {
+                [planes[0][i], planes[0][i], planes[0][i], a]
+            }
+        })
+        .collect();
+    Ok(Texture2D {
+        data: TextureData::RgbaU8(data),
+        width,
+        height,
+        ..Default::default()
+    })
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(length)
+            .ok_or(Error::PsdCorruptData)?;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(Error::PsdCorruptData)?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+///
+/// Decodes a PackBits run-length encoded row and appends it to the output.
+///
+fn unpack_bits(mut bytes: &[u8], output: &mut Vec<u8>) -> Result<()> {
+    while let Some((&header, rest)) = bytes.split_first() {
+        let header = header as i8;
+        if header >= 0 {
+            let count = header as usize + 1;
+            output.extend_from_slice(rest.get(..count).ok_or(Error::PsdCorruptData)?);
+            bytes = &rest[count..];
+        } else if header != -128 {
+            let value = *rest.first().ok_or(Error::PsdCorruptData)?;
+            output.resize(output.len() + (1 - header as isize) as usize, value);
+            bytes = &rest[1..];
+        } else {
+            bytes = rest;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn psd(compression: u16, channels: &[Vec<u8>], width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"8BPS".to_vec();
+        bytes.extend(1u16.to_be_bytes());
+        bytes.extend([0; 6]);
+        bytes.extend((channels.len() as u16).to_be_bytes());
+        bytes.extend(height.to_be_bytes());
+        bytes.extend(width.to_be_bytes());
+        bytes.extend(8u16.to_be_bytes());
+        bytes.extend(3u16.to_be_bytes());
+        bytes.extend(0u32.to_be_bytes());
+        bytes.extend(0u32.to_be_bytes());
+        // A layer section with two (ignored) layers: one covering the whole canvas and one covering the top left pixel.
+        let layers = layers(&[
+            ((0, 0, height, width), channels.to_vec()),
+            ((0, 0, 1, 1), vec![vec![1]; channels.len()]),
+        ]);
+        bytes.extend((layers.len() as u32).to_be_bytes());
+        bytes.extend(layers);
+        bytes.extend(compression.to_be_bytes());
+        if compression == 0 {
+            for channel in channels {
+                bytes.extend(channel);
+            }
+        } else {
+            // Each row is encoded as a literal run.
+            let rows: Vec<Vec<u8>> = channels
+                .iter()
+                .flat_map(|c| c.chunks(width as usize))
+                .map(|row| [&[row.len() as u8 - 1], row].concat())
+                .collect();
+            for row in rows.iter() {
+                bytes.extend((row.len() as u16).to_be_bytes());
+            }
+            for row in rows {
+                bytes.extend(row);
+            }
+        }
+        bytes
+    }
+
+    /// The bounds (top, left, bottom, right) and channel data of a layer.
+    type Layer = ((u32, u32, u32, u32), Vec<Vec<u8>>);
+
+    fn layers(layers: &[Layer]) -> Vec<u8> {
+        let mut info = (layers.len() as i16).to_be_bytes().to_vec();
+        for ((top, left, bottom, right), channels) in layers {
+            for v in [top, left, bottom, right] {
+                info.extend(v.to_be_bytes());
+            }
+            info.extend((channels.len() as u16).to_be_bytes());
+            for (id, channel) in channels.iter().enumerate() {
+                let id = if id == 3 { -1 } else { id as i16 };
+                info.extend(id.to_be_bytes());
+                info.extend((channel.len() as u32 + 2).to_be_bytes());
+            }
+            info.extend(b"8BIMnorm");
+            info.extend([255, 0, 0, 0]);
+            // Extra data: empty mask and blending ranges followed by the name "L" padded to 4 bytes.
+            let extra = [0, 0, 0, 0, 0, 0, 0, 0, 1, b'L', 0, 0];
+            info.extend((extra.len() as u32).to_be_bytes());
+            info.extend(extra);
+        }
+        for (_, channels) in layers {
+            for channel in channels {
+                info.extend(0u16.to_be_bytes());
+                info.extend(channel);
+            }
+        }
+        if info.len() % 2 == 1 {
+            info.push(0);
+        }
+        let mut section = (info.len() as u32).to_be_bytes().to_vec();
+        section.extend(info);
+        // Empty global layer mask info.
+        section.extend(0u32.to_be_bytes());
+        section
+    }
+
+    #[test]
+    pub fn composite() {
+        let channels = vec![
+            vec![255, 0, 0, 128],
+            vec![0, 255, 0, 128],
+            vec![0, 0, 255, 128],
+            vec![255, 255, 255, 0],
+        ];
+        let expected = TextureData::RgbaU8(vec![
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [0, 0, 255, 255],
+            [128, 128, 128, 0],
+        ]);
+        for compression in [0, 1] {
+            let tex = psd_from_bytes(&psd(compression, &channels, 2, 2)).unwrap();
+            assert_eq!(tex.width, 2);
+            assert_eq!(tex.height, 2);
+            assert_eq!(tex.data, expected);
+        }
+    }
+
+    #[test]
+    pub fn unpack_bits() {
+        let mut output = Vec::new();
+        super::unpack_bits(&[2, 1, 2, 3, 0xFE, 7, 0x80, 0, 9], &mut output).unwrap();
+        assert_eq!(output, vec![1, 2, 3, 7, 7, 7, 9]);
+        assert!(super::unpack_bits(&[5, 1], &mut output).is_err());
+    }
+
+    #[test]
+    pub fn deserialize_psd() {
+        let channels = vec![vec![10], vec![20], vec![30]];
+        let tex: Texture2D = crate::io::RawAssets::new()
+            .insert("test.psd", psd(1, &channels, 1, 1))
+            .deserialize("psd")
+            .unwrap();
+        assert_eq!(tex.data, TextureData::RgbaU8(vec![[10, 20, 30, 255]]));
+        assert_eq!(tex.name, "test.psd");
+    }
+
+    #[test]
+    pub fn invalid_dimensions() {
+        let mut bytes = psd(1, &[vec![0], vec![0], vec![0]], 1, 1);
+        // Width just above the limit and near u32::MAX with only a few bytes of data.
+        for width in [MAX_DIMENSION + 1, u32::MAX - 1] {
+            bytes[18..22].copy_from_slice(&width.to_be_bytes());
+            assert!(matches!(psd_from_bytes(&bytes), Err(Error::PsdCorruptData)));
+        }
+        // A valid width which does not match the data.
+        bytes[18..22].copy_from_slice(&MAX_DIMENSION.to_be_bytes());
+        assert!(matches!(psd_from_bytes(&bytes), Err(Error::PsdCorruptData)));
+    }
+
+    #[test]
+    pub fn unsupported() {
+        let bytes = psd(1, &[vec![0], vec![0], vec![0]], 1, 1);
+        assert!(psd_from_bytes(&bytes).is_ok());
+        // Version 2 is the large document format (.psb).
+        let mut psb = bytes.clone();
+        psb[4..6].copy_from_slice(&2u16.to_be_bytes());
+        assert!(matches!(psd_from_bytes(&psb), Err(Error::PsdCorruptData)));
+        for depth in [1u16, 16, 32] {
+            let mut bytes = bytes.clone();
+            bytes[22..24].copy_from_slice(&depth.to_be_bytes());
+            assert!(matches!(psd_from_bytes(&bytes), Err(Error::PsdCorruptData)));
+        }
+        // Bitmap, indexed, CMYK, multichannel, duotone and Lab.
+        for color_mode in [0u16, 2, 4, 7, 8, 9] {
+            let mut bytes = bytes.clone();
+            bytes[24..26].copy_from_slice(&color_mode.to_be_bytes());
+            assert!(matches!(psd_from_bytes(&bytes), Err(Error::PsdCorruptData)));
+        }
+        // An unknown compression method.
+        let mut bytes = bytes.clone();
+        let compression = bytes.len() - 3 * 4 - 2;
+        bytes[compression..compression + 2].copy_from_slice(&3u16.to_be_bytes());
+        assert!(matches!(psd_from_bytes(&bytes), Err(Error::PsdCorruptData)));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{io::Deserialize, Error, Result};
+use crate::{io::Deserialize, Error, Result, Texture2D, TextureOptions};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -9,8 +9,10 @@ use std::path::{Path, PathBuf};
 /// Use the [RawAssets::remove] or [RawAssets::get] function to extract the raw byte array for the assets
 /// or [RawAssets::deserialize] to deserialize an asset or [RawAssets::save] to save the assets.
 ///
+/// Relative paths given to these functions can be resolved against a base directory, see [RawAssets::set_base_path].
+///
 #[derive(Default)]
-pub struct RawAssets(HashMap<PathBuf, Vec<u8>>);
+pub struct RawAssets(HashMap<PathBuf, Vec<u8>>, Option<PathBuf>);
 
 impl RawAssets {
     ///
@@ -60,7 +62,46 @@ impl RawAssets {
         Ok(self.0.get(&self.match_path(path.as_ref())?).unwrap())
     }
 
+    ///
+    /// Sets the base directory which relative paths are resolved against when using [RawAssets::get], [RawAssets::remove] or [RawAssets::deserialize],
+    /// for example the directory of a model that references its textures by relative paths.
+    ///
+    /// A path is resolved in the following order:
+    /// - An absolute path bypasses the base directory and is matched as is.
+    /// - A relative path is first joined with the base directory and matched.
+    /// - If that fails, the relative path is matched as is, exactly as if no base directory was set.
+    ///
+    /// ```
+    /// # use three_d_asset::io::*;
+    /// let mut assets = RawAssets::new();
+    /// assets.insert("models/texture.png", vec![1]);
+    /// assets.insert("texture.png", vec![2]);
+    /// assets.set_base_path("models");
+    /// assert_eq!(assets.get("texture.png").unwrap(), &[1]);
+    /// ```
+    ///
+    pub fn set_base_path(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.1 = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    ///
+    /// Returns the base directory which relative paths are resolved against, see [RawAssets::set_base_path].
+    ///
+    pub fn base_path(&self) -> Option<&Path> {
+        self.1.as_deref()
+    }
+
     pub(crate) fn match_path(&self, path: &Path) -> Result<PathBuf> {
+        if let Some(base_path) = self.1.as_ref().filter(|_| path.is_relative()) {
+            if let Ok(p) = self.match_path_ignoring_base_path(&base_path.join(path)) {
+                return Ok(p);
+            }
+        }
+        self.match_path_ignoring_base_path(path)
+    }
+
+    fn match_path_ignoring_base_path(&self, path: &Path) -> Result<PathBuf> {
         if self.0.contains_key(path) {
             Ok(path.into())
         } else {
@@ -130,6 +171,79 @@ impl RawAssets {
         T::deserialize(path, self)
     }
 
+    ///
+    /// Deserialize the texture with the given path and applies the given filter, wrapping, mipmap and premultiplied alpha options to it.
+    /// Otherwise the same as [RawAssets::deserialize].
+    ///
+    /// ```
+    /// # use three_d_asset::io::*;
+    /// # use three_d_asset::{TextureOptions, Wrapping};
+    /// let mut assets = load(&["test_data/test.png"]).unwrap();
+    /// let texture = assets
+    ///     .deserialize_texture_with_options(
+    ///         "test.png",
+    ///         &TextureOptions {
+    ///             wrap_s: Wrapping::ClampToEdge,
+    ///             wrap_t: Wrapping::ClampToEdge,
+    ///             mip_map_filter: None,
+    ///             ..Default::default()
+    ///         },
+    ///     )
+    ///     .unwrap();
+    /// ```
+    ///
+    pub fn deserialize_texture_with_options(
+        &mut self,
+        path: impl AsRef<Path>,
+        options: &TextureOptions,
+    ) -> Result<Texture2D> {
+        let mut texture: Texture2D = self.deserialize(path)?;
+        texture.apply_options(options);
+        Ok(texture)
+    }
+
+    ///
+    /// Deserialize the texture with the given path and returns it together with a copy of the original encoded bytes,
+    /// for example to pass an unmodified JPEG through an export pipeline without recompression while still using the decoded pixels for a preview.
+    /// Otherwise the same as [RawAssets::deserialize].
+    ///
+    pub fn deserialize_texture_with_source(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<(Texture2D, Vec<u8>)> {
+        let bytes = self.get(path.as_ref())?.to_vec();
+        let texture = self.deserialize(path)?;
+        Ok((texture, bytes))
+    }
+
+    ///
+    /// Deserialize the texture with the given path and returns a copy of the given fallback texture instead of an error if the deserialization fails,
+    /// for example to show [Texture2D::missing_texture] instead of aborting the loading of a whole scene because of a single corrupt file.
+    /// The error is logged as a warning using the [log] crate.
+    ///
+    /// ```
+    /// # use three_d_asset::io::*;
+    /// # use three_d_asset::Texture2D;
+    /// let mut assets = RawAssets::new();
+    /// assets.insert("corrupt.png", vec![1, 2, 3]);
+    /// let texture = assets.deserialize_texture_or_fallback("corrupt.png", &Texture2D::missing_texture(64));
+    /// ```
+    ///
+    pub fn deserialize_texture_or_fallback(
+        &mut self,
+        path: impl AsRef<Path>,
+        fallback: &Texture2D,
+    ) -> Texture2D {
+        self.deserialize(path.as_ref()).unwrap_or_else(|e| {
+            log::warn!(
+                "failed to deserialize the texture {}, using a fallback texture instead: {}",
+                path.as_ref().display(),
+                e
+            );
+            fallback.clone()
+        })
+    }
+
     ///
     /// Saves all of the raw assets to files.
     ///
@@ -158,3 +272,49 @@ impl std::fmt::Debug for RawAssets {
         d.finish()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn base_path() {
+        let mut assets = RawAssets::new();
+        assets.insert("test_data/models/texture.png", vec![1]);
+        assets.insert("texture.png", vec![2]);
+        assets.insert("/absolute/texture.png", vec![3]);
+        assert_eq!(assets.get("texture.png").unwrap(), &[2]);
+
+        assets.set_base_path("test_data/models");
+        assert_eq!(assets.base_path(), Some(Path::new("test_data/models")));
+        assert_eq!(assets.get("texture.png").unwrap(), &[1]);
+        assert_eq!(assets.get("/absolute/texture.png").unwrap(), &[3]);
+        assert_eq!(assets.remove("texture.png").unwrap(), vec![1]);
+        assert_eq!(assets.get("texture.png").unwrap(), &[2]);
+    }
+
+    #[test]
+    pub fn deserialize_texture_or_fallback() {
+        let mut assets = RawAssets::new();
+        assets.insert("corrupt.png", vec![1, 2, 3]);
+        let fallback = Texture2D::missing_texture(16);
+        assert_eq!(fallback.data.len(), 256);
+        assert_eq!(
+            assets.deserialize_texture_or_fallback("corrupt.png", &fallback),
+            fallback
+        );
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    pub fn deserialize_texture_with_source() {
+        let bytes = std::fs::read("test_data/Cube_BaseColor.png").unwrap();
+        let mut assets = RawAssets::new();
+        assets.insert("Cube_BaseColor.png", bytes.clone());
+        let (texture, source) = assets
+            .deserialize_texture_with_source("Cube_BaseColor.png")
+            .unwrap();
+        assert_eq!(source, bytes);
+        assert_eq!(texture.width, 512);
+    }
+}
@@ -15,3 +15,23 @@ pub fn save(raw_assets: &RawAssets) -> crate::Result<()> {
     }
     Ok(())
 }
+
+///
+/// Serializes the given texture into the image format given by the extension of the path and saves it.
+/// The pixel data can be of any [TextureData](crate::TextureData) variant; the luminance, luminance and alpha, RGB or RGBA
+/// 8-bit color type is chosen based on the number of channels and floating point data is clamped to the `[0..1]` range before it is converted to 8-bit.
+/// The texture is encoded before the file is created, so an existing file is left untouched if encoding fails.
+///
+pub fn save_texture_pixels(
+    path: impl AsRef<std::path::Path>,
+    texture: &crate::Texture2D,
+) -> crate::Result<()> {
+    #[cfg(feature = "image")]
+    if let Ok(format) = image::ImageFormat::from_path(&path) {
+        let mut writer = std::io::Cursor::new(Vec::new());
+        texture.write_to(&mut writer, format)?;
+        std::fs::write(&path, writer.into_inner())?;
+        return Ok(());
+    }
+    save(&texture.serialize(path)?)
+}
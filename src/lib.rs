@@ -218,6 +218,9 @@ pub enum Error {
     #[cfg(feature = "image")]
     #[error("error while parsing an image file")]
     Image(#[from] image::ImageError),
+    #[cfg(feature = "tiff")]
+    #[error("error while parsing a .tiff file")]
+    Tiff(#[from] tiff::TiffError),
     #[cfg(feature = "obj")]
     #[error("error while parsing an .obj file")]
     Obj(#[from] wavefront_obj::ParseError),
@@ -240,6 +243,21 @@ pub enum Error {
     GltfMissingData,
     #[error("the .vol file contain wrong data size")]
     VolCorruptData,
+    #[error("the .psd file contain corrupt or unsupported data")]
+    PsdCorruptData,
+    #[error("the .cube file contain corrupt or unsupported data")]
+    CubeCorruptData,
+    #[error("the .ktx2 file contain corrupt or unsupported data")]
+    Ktx2CorruptData,
+    #[cfg(feature = "exr")]
+    #[error("error while parsing an .exr file")]
+    Exr(#[from] exr::error::Error),
+    #[cfg(feature = "exr")]
+    #[error("the .exr file does not contain any layers")]
+    ExrCorruptData,
+    #[cfg(feature = "exr")]
+    #[error("the .exr file does not contain the layer {0}, the available layers are: {1}")]
+    ExrLayerNotFound(String, String),
     #[cfg(not(target_arch = "wasm32"))]
     #[error("error while loading the file {0}: {1}")]
     FailedLoading(String, std::io::Error),
@@ -250,11 +268,17 @@ pub enum Error {
     #[error("error while loading the url {0}: {1}")]
     FailedLoadingUrl(String, String),
     #[cfg(feature = "reqwest")]
+    #[error("error while loading the url {0}: the server responded with status code {1}")]
+    FailedLoadingUrlWithStatusCode(String, u16),
+    #[cfg(feature = "reqwest")]
     #[error("error while parsing the url {0}")]
     FailedParsingUrl(String),
     #[cfg(feature = "data-url")]
     #[error("error while parsing data-url {0}: {1}")]
     FailedParsingDataUrl(String, String),
+    #[cfg(feature = "zip")]
+    #[error("error while reading a zip archive")]
+    Zip(#[from] zip::result::ZipError),
     #[error("tried to use {0} which was not loaded or otherwise added to the raw assets")]
     NotLoaded(String),
     #[error("the feature {0} is needed")]
@@ -265,4 +289,24 @@ pub enum Error {
     FailedSerialize(String),
     #[error("failed to find {0} in the file {1}")]
     FailedConvertion(String, String),
+    #[error("the texture size {0}x{1} does not match the expected texture size {2}x{3}")]
+    TextureSizeMismatch(u32, u32, u32, u32),
+    #[error(
+        "the region {2}x{3} at position ({0}, {1}) does not fit inside the texture of size {4}x{5}"
+    )]
+    RegionOutOfBounds(u32, u32, u32, u32, u32, u32),
+    #[error(
+        "the texture size {2}x{3} cannot be divided into a grid of {0}x{1} equally sized cells"
+    )]
+    InvalidGrid(u32, u32, u32, u32),
+    #[error("the texture data format does not match the format of the target texture")]
+    TextureDataFormatMismatch,
+    #[error("the channel index {0} is out of range for texture data with {1} channels")]
+    InvalidChannelIndex(usize, u32),
+    #[error("mipmaps are only supported for textures with power of two width and height, actual size is {width}x{height}")]
+    MipmapUnsupported { width: u32, height: u32 },
+    #[error("decoding {0} took {1:?}, which exceeds the limit of {2:?}")]
+    DecodeTimeExceeded(String, std::time::Duration, std::time::Duration),
+    #[error("the file {0} ended unexpectedly after {1} bytes, it is probably truncated")]
+    Truncated(String, usize),
 }
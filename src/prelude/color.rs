@@ -39,14 +39,7 @@ impl Srgba {
     /// Returns the color in linear sRGB color space.
     ///
     pub fn to_linear_srgb(&self) -> Vec4 {
-        let convert = |c: u8| {
-            let c = c as f32 / 255.0;
-            if c < 0.04045 {
-                c / 12.92
-            } else {
-                ((c + 0.055) / 1.055).powf(2.4)
-            }
-        };
+        let convert = |c: u8| srgb_to_linear(c as f32 / 255.0);
         vec4(
             convert(self.r),
             convert(self.g),
@@ -191,3 +184,25 @@ impl Default for Srgba {
         Self::WHITE
     }
 }
+
+///
+/// Converts a normalized color component from sRGB to linear color space.
+///
+pub(crate) fn srgb_to_linear(c: f32) -> f32 {
+    if c < 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+///
+/// Converts a normalized color component from linear to sRGB color space.
+///
+pub(crate) fn linear_to_srgb(c: f32) -> f32 {
+    if c < 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
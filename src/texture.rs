@@ -8,6 +8,18 @@ pub use texture2d::*;
 pub(crate) mod texture3d;
 pub use texture3d::*;
 
+pub(crate) mod texture2d_array;
+pub use texture2d_array::*;
+
+pub(crate) mod indexed_texture;
+pub use indexed_texture::*;
+
+pub(crate) mod atlas;
+pub use atlas::*;
+
+pub(crate) mod color_lut;
+pub use color_lut::*;
+
 pub use crate::prelude::f16;
 use crate::Srgba;
 
@@ -42,6 +54,41 @@ pub enum Wrapping {
     ClampToEdge,
 }
 
+///
+/// Possible filter kernels used to downsample a texture to the next mipmap level, see [Texture2D::generate_mipmaps].
+/// The kernels are listed from the softest and fastest to the sharpest.
+///
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DownsampleKernel {
+    /// Averages each 2x2 block of texels.
+    #[default]
+    Box,
+    /// A tent filter which also includes the closest texels outside of each 2x2 block with a lower weight.
+    Triangle,
+    /// A windowed sinc filter with a Kaiser window, which is sharper than the triangle filter with less ringing than the Lanczos filter.
+    Kaiser,
+    /// A Lanczos filter with three lobes, which keeps the most detail but can introduce ringing at sharp edges.
+    Lanczos,
+}
+
+///
+/// Possible orientation changes of a [Texture2D], for example used to match the face conventions of different graphics APIs, see [Texture2D::reorient].
+/// The rotations are clockwise.
+///
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureOrientation {
+    #[default]
+    Identity,
+    FlipHorizontal,
+    FlipVertical,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
 ///
 /// The pixel/texel data for a [Texture2D] or [Texture3D].
 ///
@@ -79,6 +126,15 @@ pub enum TextureData {
     /// One byte in the red, green, blue and alpha channel.
     RgbaU8(Vec<[u8; 4]>),
 
+    /// 16-bit unsigned integer in the red channel.
+    RU16(Vec<u16>),
+    /// 16-bit unsigned integer in the red and green channel.
+    RgU16(Vec<[u16; 2]>),
+    /// 16-bit unsigned integer in the red, green and blue channel.
+    RgbU16(Vec<[u16; 3]>),
+    /// 16-bit unsigned integer in the red, green, blue and alpha channel.
+    RgbaU16(Vec<[u16; 4]>),
+
     /// 16-bit float in the red channel.
     RF16(Vec<f16>),
     /// 16-bit float in the red and green channel.
@@ -98,6 +154,117 @@ pub enum TextureData {
     RgbaF32(Vec<[f32; 4]>),
 }
 
+///
+/// Describes the memory layout of a [TextureData] variant, see [TextureData::descriptor].
+/// Makes it possible to map texture data to a GPU texture format without matching on every variant.
+///
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextureDescriptor {
+    /// The number of channels per texel, between 1 and 4.
+    pub channels: u32,
+    /// The number of bits of each channel, ie. 8, 16 or 32.
+    pub bits_per_channel: u32,
+    /// Whether the channels are floating point values or unsigned normalized integers.
+    pub is_float: bool,
+    /// Whether the data is assumed to be in sRGB color space when loaded, which is the case for 8-bit RGB and RGBA data (see [TextureData::to_linear_srgb]).
+    pub is_srgb_candidate: bool,
+}
+
+///
+/// The pixel format of a [TextureData] variant without the data itself, see [TextureData::format].
+/// The number is the number of bits per channel and the `F` suffix denotes floating point channels, otherwise the channels are unsigned normalized integers.
+///
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureFormat {
+    R8,
+    Rg8,
+    Rgb8,
+    Rgba8,
+    R16,
+    Rg16,
+    Rgb16,
+    Rgba16,
+    R16F,
+    Rg16F,
+    Rgb16F,
+    Rgba16F,
+    R32F,
+    Rg32F,
+    Rgb32F,
+    Rgba32F,
+}
+
+impl TextureFormat {
+    ///
+    /// Returns the number of channels per texel, between 1 and 4.
+    ///
+    pub fn channel_count(&self) -> u32 {
+        match self {
+            Self::R8 | Self::R16 | Self::R16F | Self::R32F => 1,
+            Self::Rg8 | Self::Rg16 | Self::Rg16F | Self::Rg32F => 2,
+            Self::Rgb8 | Self::Rgb16 | Self::Rgb16F | Self::Rgb32F => 3,
+            Self::Rgba8 | Self::Rgba16 | Self::Rgba16F | Self::Rgba32F => 4,
+        }
+    }
+
+    ///
+    /// Returns the number of bytes of each channel, ie. 1, 2 or 4.
+    ///
+    pub fn bytes_per_channel(&self) -> u32 {
+        match self {
+            Self::R8 | Self::Rg8 | Self::Rgb8 | Self::Rgba8 => 1,
+            Self::R16 | Self::Rg16 | Self::Rgb16 | Self::Rgba16 => 2,
+            Self::R16F | Self::Rg16F | Self::Rgb16F | Self::Rgba16F => 2,
+            Self::R32F | Self::Rg32F | Self::Rgb32F | Self::Rgba32F => 4,
+        }
+    }
+
+    ///
+    /// Returns whether the channels are floating point values or unsigned normalized integers.
+    ///
+    pub fn is_float(&self) -> bool {
+        matches!(
+            self,
+            Self::R16F
+                | Self::Rg16F
+                | Self::Rgb16F
+                | Self::Rgba16F
+                | Self::R32F
+                | Self::Rg32F
+                | Self::Rgb32F
+                | Self::Rgba32F
+        )
+    }
+}
+
+///
+/// The matrix used to convert between YUV (YCbCr) and RGB colors, see [Texture2D::from_yuv420].
+///
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum YuvMatrix {
+    /// The ITU-R BT.601 matrix used for standard definition video.
+    Bt601,
+    /// The ITU-R BT.709 matrix used for high definition video.
+    #[default]
+    Bt709,
+}
+
+///
+/// The byte order of multi-byte channel values in raw texture bytes, see [TextureData::from_bytes].
+///
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
 impl std::fmt::Debug for TextureData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -105,6 +272,10 @@ impl std::fmt::Debug for TextureData {
             Self::RgU8(values) => write!(f, "RG u8 ({:?})", values.len()),
             Self::RgbU8(values) => write!(f, "RGB u8 ({:?})", values.len()),
             Self::RgbaU8(values) => write!(f, "RGBA u8 ({:?})", values.len()),
+            Self::RU16(values) => write!(f, "R u16 ({:?})", values.len()),
+            Self::RgU16(values) => write!(f, "RG u16 ({:?})", values.len()),
+            Self::RgbU16(values) => write!(f, "RGB u16 ({:?})", values.len()),
+            Self::RgbaU16(values) => write!(f, "RGBA u16 ({:?})", values.len()),
             Self::RF16(values) => write!(f, "R f16 ({:?})", values.len()),
             Self::RgF16(values) => write!(f, "RG f16 ({:?})", values.len()),
             Self::RgbF16(values) => write!(f, "RGB f16 ({:?})", values.len()),
@@ -134,4 +305,410 @@ impl TextureData {
             _ => {}
         };
     }
+
+    ///
+    /// Returns a copy of this texture data where `f(value, channel_index)` is applied to each channel of each texel,
+    /// which is bit-exact since the values are not converted to floating point, for example to scale a channel with saturation.
+    /// Returns an unmodified copy if the data is not one of the 8-bit formats [TextureData::RU8], [TextureData::RgU8], [TextureData::RgbU8] or [TextureData::RgbaU8].
+    ///
+    pub fn map_u8<F: Fn(u8, usize) -> u8>(&self, f: F) -> Self {
+        fn map<const N: usize>(values: &[[u8; N]], f: impl Fn(u8, usize) -> u8) -> Vec<[u8; N]> {
+            values
+                .iter()
+                .map(|texel| {
+                    let mut texel = *texel;
+                    for (i, value) in texel.iter_mut().enumerate() {
+                        *value = f(*value, i);
+                    }
+                    texel
+                })
+                .collect()
+        }
+        match self {
+            Self::RU8(values) => Self::RU8(values.iter().map(|v| f(*v, 0)).collect()),
+            Self::RgU8(values) => Self::RgU8(map(values, f)),
+            Self::RgbU8(values) => Self::RgbU8(map(values, f)),
+            Self::RgbaU8(values) => Self::RgbaU8(map(values, f)),
+            _ => self.clone(),
+        }
+    }
+
+    ///
+    /// Returns the pixel format of this texture data.
+    ///
+    pub fn format(&self) -> TextureFormat {
+        match self {
+            Self::RU8(_) => TextureFormat::R8,
+            Self::RgU8(_) => TextureFormat::Rg8,
+            Self::RgbU8(_) => TextureFormat::Rgb8,
+            Self::RgbaU8(_) => TextureFormat::Rgba8,
+            Self::RU16(_) => TextureFormat::R16,
+            Self::RgU16(_) => TextureFormat::Rg16,
+            Self::RgbU16(_) => TextureFormat::Rgb16,
+            Self::RgbaU16(_) => TextureFormat::Rgba16,
+            Self::RF16(_) => TextureFormat::R16F,
+            Self::RgF16(_) => TextureFormat::Rg16F,
+            Self::RgbF16(_) => TextureFormat::Rgb16F,
+            Self::RgbaF16(_) => TextureFormat::Rgba16F,
+            Self::RF32(_) => TextureFormat::R32F,
+            Self::RgF32(_) => TextureFormat::Rg32F,
+            Self::RgbF32(_) => TextureFormat::Rgb32F,
+            Self::RgbaF32(_) => TextureFormat::Rgba32F,
+        }
+    }
+
+    ///
+    /// Constructs texture data with the given format from raw, tightly packed bytes, where 16-bit integer, 16-bit float and 32-bit float
+    /// channel values are read with the given byte order. Trailing bytes that do not make up a whole texel are ignored.
+    ///
+    pub fn from_bytes(format: TextureFormat, bytes: &[u8], endianness: Endianness) -> Self {
+        let u16s = || {
+            bytes.chunks_exact(2).map(move |b| match endianness {
+                Endianness::Little => u16::from_le_bytes([b[0], b[1]]),
+                Endianness::Big => u16::from_be_bytes([b[0], b[1]]),
+            })
+        };
+        let f16s = || u16s().map(f16::from_bits);
+        let f32s = || {
+            bytes.chunks_exact(4).map(move |b| match endianness {
+                Endianness::Little => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                Endianness::Big => f32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+            })
+        };
+        let u8s = || bytes.iter().copied();
+        fn texels<T: Copy + Default, const N: usize>(
+            values: impl Iterator<Item = T>,
+        ) -> Vec<[T; N]> {
+            let values: Vec<T> = values.collect();
+            values
+                .chunks_exact(N)
+                .map(|c| {
+                    let mut texel = [T::default(); N];
+                    texel.copy_from_slice(c);
+                    texel
+                })
+                .collect()
+        }
+        match format {
+            TextureFormat::R8 => Self::RU8(bytes.to_vec()),
+            TextureFormat::Rg8 => Self::RgU8(texels(u8s())),
+            TextureFormat::Rgb8 => Self::RgbU8(texels(u8s())),
+            TextureFormat::Rgba8 => Self::RgbaU8(texels(u8s())),
+            TextureFormat::R16 => Self::RU16(u16s().collect()),
+            TextureFormat::Rg16 => Self::RgU16(texels(u16s())),
+            TextureFormat::Rgb16 => Self::RgbU16(texels(u16s())),
+            TextureFormat::Rgba16 => Self::RgbaU16(texels(u16s())),
+            TextureFormat::R16F => Self::RF16(f16s().collect()),
+            TextureFormat::Rg16F => Self::RgF16(texels(f16s())),
+            TextureFormat::Rgb16F => Self::RgbF16(texels(f16s())),
+            TextureFormat::Rgba16F => Self::RgbaF16(texels(f16s())),
+            TextureFormat::R32F => Self::RF32(f32s().collect()),
+            TextureFormat::Rg32F => Self::RgF32(texels(f32s())),
+            TextureFormat::Rgb32F => Self::RgbF32(texels(f32s())),
+            TextureFormat::Rgba32F => Self::RgbaF32(texels(f32s())),
+        }
+    }
+
+    ///
+    /// Returns a description of the memory layout of this texture data.
+    ///
+    pub fn descriptor(&self) -> TextureDescriptor {
+        let format = self.format();
+        TextureDescriptor {
+            channels: format.channel_count(),
+            bits_per_channel: format.bytes_per_channel() * 8,
+            is_float: format.is_float(),
+            is_srgb_candidate: matches!(self, Self::RgbU8(_) | Self::RgbaU8(_)),
+        }
+    }
+
+    ///
+    /// Returns the number of texels in the texture data.
+    ///
+    pub fn len(&self) -> usize {
+        match self {
+            Self::RU8(values) => values.len(),
+            Self::RgU8(values) => values.len(),
+            Self::RgbU8(values) => values.len(),
+            Self::RgbaU8(values) => values.len(),
+            Self::RU16(values) => values.len(),
+            Self::RgU16(values) => values.len(),
+            Self::RgbU16(values) => values.len(),
+            Self::RgbaU16(values) => values.len(),
+            Self::RF16(values) => values.len(),
+            Self::RgF16(values) => values.len(),
+            Self::RgbF16(values) => values.len(),
+            Self::RgbaF16(values) => values.len(),
+            Self::RF32(values) => values.len(),
+            Self::RgF32(values) => values.len(),
+            Self::RgbF32(values) => values.len(),
+            Self::RgbaF32(values) => values.len(),
+        }
+    }
+
+    ///
+    /// Returns whether or not the texture data contains any texels.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///
+    /// Returns whether or not the texture data has an alpha channel.
+    /// The two channel formats are interpreted as luminance and alpha, the same way as when an image is loaded or saved.
+    ///
+    pub fn has_alpha(&self) -> bool {
+        matches!(
+            self,
+            Self::RgU8(_)
+                | Self::RgbaU8(_)
+                | Self::RgU16(_)
+                | Self::RgbaU16(_)
+                | Self::RgF16(_)
+                | Self::RgbaF16(_)
+                | Self::RgF32(_)
+                | Self::RgbaF32(_)
+        )
+    }
+
+    ///
+    /// Returns an iterator over all texels as normalized RGBA values without allocating a copy of the texture data.
+    /// One channel data is interpreted as luminance and two channel data as luminance and alpha, ie. `[l, l, l, 1.0]` and `[l, l, l, a]`,
+    /// and 8-bit and 16-bit data is divided by 255 and 65535 respectively while floating point data is returned as is.
+    ///
+    pub fn iter_rgba_f32(&self) -> impl Iterator<Item = [f32; 4]> + '_ {
+        (0..self.len()).map(|i| self.rgba_f32(i))
+    }
+
+    ///
+    /// Returns the texel at the given index as a normalized RGBA value.
+    /// One channel data is interpreted as luminance and two channel data as luminance and alpha.
+    ///
+    pub(crate) fn rgba_f32(&self, index: usize) -> [f32; 4] {
+        let u = |v: u8| v as f32 / 255.0;
+        let w = |v: u16| v as f32 / 65535.0;
+        match self {
+            Self::RU8(values) => {
+                let l = u(values[index]);
+                [l, l, l, 1.0]
+            }
+            Self::RgU8(values) => {
+                let [l, a] = values[index];
+                [u(l), u(l), u(l), u(a)]
+            }
+            Self::RgbU8(values) => {
+                let [r, g, b] = values[index];
+                [u(r), u(g), u(b), 1.0]
+            }
+            Self::RgbaU8(values) => values[index].map(u),
+            Self::RU16(values) => {
+                let l = w(values[index]);
+                [l, l, l, 1.0]
+            }
+            Self::RgU16(values) => {
+                let [l, a] = values[index].map(w);
+                [l, l, l, a]
+            }
+            Self::RgbU16(values) => {
+                let [r, g, b] = values[index].map(w);
+                [r, g, b, 1.0]
+            }
+            Self::RgbaU16(values) => values[index].map(w),
+            Self::RF16(values) => {
+                let l = values[index].to_f32();
+                [l, l, l, 1.0]
+            }
+            Self::RgF16(values) => {
+                let [l, a] = values[index].map(|v| v.to_f32());
+                [l, l, l, a]
+            }
+            Self::RgbF16(values) => {
+                let [r, g, b] = values[index].map(|v| v.to_f32());
+                [r, g, b, 1.0]
+            }
+            Self::RgbaF16(values) => values[index].map(|v| v.to_f32()),
+            Self::RF32(values) => {
+                let l = values[index];
+                [l, l, l, 1.0]
+            }
+            Self::RgF32(values) => {
+                let [l, a] = values[index];
+                [l, l, l, a]
+            }
+            Self::RgbF32(values) => {
+                let [r, g, b] = values[index];
+                [r, g, b, 1.0]
+            }
+            Self::RgbaF32(values) => values[index],
+        }
+    }
+
+    ///
+    /// Constructs texture data of the same format as this texture data from the given normalized RGBA texels.
+    /// This is the inverse of [TextureData::iter_rgba_f32], ie. the luminance of one and two channel formats is taken from the red channel.
+    ///
+    pub(crate) fn same_format_from_rgba_f32(
+        &self,
+        texels: impl IntoIterator<Item = [f32; 4]>,
+    ) -> Self {
+        let u = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let w = |v: f32| (v.clamp(0.0, 1.0) * 65535.0).round() as u16;
+        let h = f16::from_f32;
+        let texels = texels.into_iter();
+        match self {
+            Self::RU8(_) => Self::RU8(texels.map(|c| u(c[0])).collect()),
+            Self::RgU8(_) => Self::RgU8(texels.map(|c| [u(c[0]), u(c[3])]).collect()),
+            Self::RgbU8(_) => Self::RgbU8(texels.map(|c| [u(c[0]), u(c[1]), u(c[2])]).collect()),
+            Self::RgbaU8(_) => Self::RgbaU8(texels.map(|c| c.map(u)).collect()),
+            Self::RU16(_) => Self::RU16(texels.map(|c| w(c[0])).collect()),
+            Self::RgU16(_) => Self::RgU16(texels.map(|c| [w(c[0]), w(c[3])]).collect()),
+            Self::RgbU16(_) => Self::RgbU16(texels.map(|c| [w(c[0]), w(c[1]), w(c[2])]).collect()),
+            Self::RgbaU16(_) => Self::RgbaU16(texels.map(|c| c.map(w)).collect()),
+            Self::RF16(_) => Self::RF16(texels.map(|c| h(c[0])).collect()),
+            Self::RgF16(_) => Self::RgF16(texels.map(|c| [h(c[0]), h(c[3])]).collect()),
+            Self::RgbF16(_) => Self::RgbF16(texels.map(|c| [h(c[0]), h(c[1]), h(c[2])]).collect()),
+            Self::RgbaF16(_) => Self::RgbaF16(texels.map(|c| c.map(h)).collect()),
+            Self::RF32(_) => Self::RF32(texels.map(|c| c[0]).collect()),
+            Self::RgF32(_) => Self::RgF32(texels.map(|c| [c[0], c[3]]).collect()),
+            Self::RgbF32(_) => Self::RgbF32(texels.map(|c| [c[0], c[1], c[2]]).collect()),
+            Self::RgbaF32(_) => Self::RgbaF32(texels.collect()),
+        }
+    }
+}
+
+pub(crate) fn premultiply(c: [f32; 4]) -> [f32; 4] {
+    [c[0] * c[3], c[1] * c[3], c[2] * c[3], c[3]]
+}
+
+pub(crate) fn unpremultiply(c: [f32; 4]) -> [f32; 4] {
+    if c[3] > 0.0 {
+        [c[0] / c[3], c[1] / c[3], c[2] / c[3], c[3]]
+    } else {
+        c
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn iter_rgba_f32() {
+        let data = vec![[0, 51, 102, 255], [255, 204, 153, 0], [10, 20, 30, 40]];
+        let texels: Vec<[f32; 4]> = TextureData::RgbaU8(data.clone()).iter_rgba_f32().collect();
+        assert_eq!(
+            texels,
+            data.iter()
+                .map(|c| c.map(|v| v as f32 / 255.0))
+                .collect::<Vec<_>>()
+        );
+
+        let texels: Vec<[f32; 4]> = TextureData::RgU8(vec![[51, 102]]).iter_rgba_f32().collect();
+        assert_eq!(texels, vec![[0.2, 0.2, 0.2, 0.4]]);
+
+        let texels: Vec<[f32; 4]> = TextureData::RgbF16(vec![[f16::from_f32(0.5); 3]])
+            .iter_rgba_f32()
+            .collect();
+        assert_eq!(texels, vec![[0.5, 0.5, 0.5, 1.0]]);
+    }
+
+    #[test]
+    pub fn map_u8() {
+        let data = TextureData::RgbU8(vec![[10, 100, 200], [0, 128, 255], [255, 127, 0]]);
+        assert_eq!(
+            data.map_u8(|v, i| if i == 1 { v.saturating_mul(2) } else { v }),
+            TextureData::RgbU8(vec![[10, 200, 200], [0, 255, 255], [255, 254, 0]])
+        );
+
+        let data = TextureData::RF32(vec![0.5]);
+        assert_eq!(data.map_u8(|v, _| v / 2), data);
+    }
+
+    #[test]
+    pub fn descriptor() {
+        assert_eq!(
+            TextureData::RgbaF16(Vec::new()).descriptor(),
+            TextureDescriptor {
+                channels: 4,
+                bits_per_channel: 16,
+                is_float: true,
+                is_srgb_candidate: false,
+            }
+        );
+        assert_eq!(
+            TextureData::RU8(Vec::new()).descriptor(),
+            TextureDescriptor {
+                channels: 1,
+                bits_per_channel: 8,
+                is_float: false,
+                is_srgb_candidate: false,
+            }
+        );
+        assert!(
+            TextureData::RgbaU8(Vec::new())
+                .descriptor()
+                .is_srgb_candidate
+        );
+    }
+
+    #[test]
+    pub fn format() {
+        let h = f16::from_f32(0.5);
+        let formats = [
+            (TextureData::RU8(vec![0]), TextureFormat::R8),
+            (TextureData::RgU8(vec![[0; 2]]), TextureFormat::Rg8),
+            (TextureData::RgbU8(vec![[0; 3]]), TextureFormat::Rgb8),
+            (TextureData::RgbaU8(vec![[0; 4]]), TextureFormat::Rgba8),
+            (TextureData::RU16(vec![0]), TextureFormat::R16),
+            (TextureData::RgU16(vec![[0; 2]]), TextureFormat::Rg16),
+            (TextureData::RgbU16(vec![[0; 3]]), TextureFormat::Rgb16),
+            (TextureData::RgbaU16(vec![[0; 4]]), TextureFormat::Rgba16),
+            (TextureData::RF16(vec![h]), TextureFormat::R16F),
+            (TextureData::RgF16(vec![[h; 2]]), TextureFormat::Rg16F),
+            (TextureData::RgbF16(vec![[h; 3]]), TextureFormat::Rgb16F),
+            (TextureData::RgbaF16(vec![[h; 4]]), TextureFormat::Rgba16F),
+            (TextureData::RF32(vec![0.0]), TextureFormat::R32F),
+            (TextureData::RgF32(vec![[0.0; 2]]), TextureFormat::Rg32F),
+            (TextureData::RgbF32(vec![[0.0; 3]]), TextureFormat::Rgb32F),
+            (TextureData::RgbaF32(vec![[0.0; 4]]), TextureFormat::Rgba32F),
+        ];
+        for (i, (data, format)) in formats.into_iter().enumerate() {
+            assert_eq!(data.format(), format);
+            assert_eq!(format.channel_count(), i as u32 % 4 + 1);
+            assert_eq!(format.bytes_per_channel(), [1, 2, 2, 4][i / 4]);
+            assert_eq!(format.is_float(), i >= 8);
+        }
+    }
+
+    #[test]
+    pub fn from_bytes() {
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0xFF];
+        assert_eq!(
+            TextureData::from_bytes(TextureFormat::R16, &bytes, Endianness::Little),
+            TextureData::RU16(vec![0x0201, 0x0403])
+        );
+        assert_eq!(
+            TextureData::from_bytes(TextureFormat::R16, &bytes, Endianness::Big),
+            TextureData::RU16(vec![0x0102, 0x0304])
+        );
+        assert_eq!(
+            TextureData::from_bytes(TextureFormat::Rg8, &bytes, Endianness::Big),
+            TextureData::RgU8(vec![[1, 2], [3, 4]])
+        );
+
+        let bytes = 1.5f32.to_be_bytes();
+        assert_eq!(
+            TextureData::from_bytes(TextureFormat::R32F, &bytes, Endianness::Big),
+            TextureData::RF32(vec![1.5])
+        );
+        assert_ne!(
+            TextureData::from_bytes(TextureFormat::R32F, &bytes, Endianness::Little),
+            TextureData::RF32(vec![1.5])
+        );
+        let bytes = f16::from_f32(-2.0).to_bits().to_be_bytes();
+        assert_eq!(
+            TextureData::from_bytes(TextureFormat::R16F, &bytes, Endianness::Big),
+            TextureData::RF16(vec![f16::from_f32(-2.0)])
+        );
+    }
 }
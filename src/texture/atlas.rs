@@ -0,0 +1,376 @@
+use crate::texture::Texture2D;
+use std::collections::HashMap;
+
+///
+/// Packs a set of named [Texture2D]s into a single texture atlas, which for example is useful to reduce the number of draw calls.
+///
+/// ```
+/// # use three_d_asset::*;
+/// let mut builder = AtlasBuilder::new();
+/// builder
+///     .set_padding(1)
+///     .add("a", Texture2D::default())
+///     .add("b", Texture2D::default());
+/// let (atlas, rects) = builder.build();
+/// let [u_min, v_min, u_max, v_max] = rects["a"];
+/// ```
+///
+#[derive(Clone, Debug, Default)]
+pub struct AtlasBuilder {
+    textures: Vec<(String, Texture2D)>,
+    padding: u32,
+}
+
+impl AtlasBuilder {
+    ///
+    /// Constructs a new builder without any textures and no padding.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Sets the number of pixels between the packed textures. The padding is filled with the closest edge texels of each texture,
+    /// so that linear interpolation and mipmapping does not bleed the neighbouring textures into each other.
+    ///
+    pub fn set_padding(&mut self, padding: u32) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    ///
+    /// Adds a texture to the atlas. The name is used as the key in the map of uv rectangles returned by [AtlasBuilder::build].
+    ///
+    pub fn add(&mut self, name: impl Into<String>, texture: Texture2D) -> &mut Self {
+        self.textures.push((name.into(), texture));
+        self
+    }
+
+    ///
+    /// Packs the added textures into a single texture using a shelf packing algorithm and returns it together with the uv rectangle of each texture.
+    /// The uv rectangles are given as `[u_min, v_min, u_max, v_max]` in the `[0..1]` range with `(0, 0)` at the first texel of the [TextureData](crate::TextureData).
+    ///
+    /// The atlas has the [TextureData](crate::TextureData) format of the first added texture and the other textures are converted to that format.
+    /// Textures with zero width or height are not packed and get an empty uv rectangle.
+    ///
+    pub fn build(&self) -> (Texture2D, HashMap<String, [f32; 4]>) {
+        let first = match self.textures.first() {
+            Some((_, texture)) => texture,
+            None => return (Texture2D::default(), HashMap::new()),
+        };
+        let padding = self.padding;
+        let packed = || {
+            self.textures
+                .iter()
+                .filter(|(_, t)| t.width > 0 && t.height > 0)
+        };
+        let area: u64 = packed()
+            .map(|(_, t)| (t.width + 2 * padding) as u64 * (t.height + 2 * padding) as u64)
+            .sum();
+        let max_width = packed()
+            .map(|(_, t)| t.width + 2 * padding)
+            .max()
+            .unwrap_or(0);
+        let width = ((area as f64).sqrt().ceil() as u32)
+            .next_power_of_two()
+            .max(max_width);
+
+        // Place the textures on shelves, the highest textures first.
+        let mut order: Vec<usize> = (0..self.textures.len()).collect();
+        order.sort_by_key(|i| std::cmp::Reverse(self.textures[*i].1.height));
+        let mut positions = vec![(0, 0); self.textures.len()];
+        let (mut x, mut y, mut shelf_height) = (0, 0, 0);
+        for i in order {
+            let texture = &self.textures[i].1;
+            if texture.width == 0 || texture.height == 0 {
+                continue;
+            }
+            let (w, h) = (texture.width + 2 * padding, texture.height + 2 * padding);
+            if x + w > width {
+                x = 0;
+                y += shelf_height;
+                shelf_height = 0;
+            }
+            positions[i] = (x + padding, y + padding);
+            x += w;
+            shelf_height = shelf_height.max(h);
+        }
+        let height = y + shelf_height;
+
+        let mut texels = vec![[0.0; 4]; (width * height) as usize];
+        let mut rects = HashMap::new();
+        for ((name, texture), (x0, y0)) in self.textures.iter().zip(positions) {
+            if texture.width == 0 || texture.height == 0 {
+                rects.insert(name.clone(), [0.0; 4]);
+                continue;
+            }
+            for y in 0..texture.height + 2 * padding {
+                for x in 0..texture.width + 2 * padding {
+                    let sx = x.saturating_sub(padding).min(texture.width - 1);
+                    let sy = y.saturating_sub(padding).min(texture.height - 1);
+                    let color = texture.data.rgba_f32((sy * texture.width + sx) as usize);
+                    let color = if texture.premultiplied {
+                        crate::texture::unpremultiply(color)
+                    } else {
+                        color
+                    };
+                    texels[((y0 + y - padding) * width + x0 + x - padding) as usize] = color;
+                }
+            }
+            rects.insert(
+                name.clone(),
+                [
+                    x0 as f32 / width as f32,
+                    y0 as f32 / height as f32,
+                    (x0 + texture.width) as f32 / width as f32,
+                    (y0 + texture.height) as f32 / height as f32,
+                ],
+            );
+        }
+        let atlas = Texture2D {
+            name: "atlas".to_owned(),
+            data: first.data.same_format_from_rgba_f32(texels),
+            width,
+            height,
+            ..Default::default()
+        };
+        (atlas, rects)
+    }
+}
+
+///
+/// A rectangle of pixels within a texture, for example the location of a sprite in a [SpriteSheet].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    /// The x coordinate of the first column of the rectangle.
+    pub x: u32,
+    /// The y coordinate of the first row of the rectangle.
+    pub y: u32,
+    /// The number of columns in the rectangle.
+    pub width: u32,
+    /// The number of rows in the rectangle.
+    pub height: u32,
+}
+
+///
+/// A texture containing a number of sprites, for example the frames of an animation or the icons of a user interface.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpriteSheet {
+    /// The texture containing the sprites.
+    pub texture: Texture2D,
+}
+
+impl From<Texture2D> for SpriteSheet {
+    fn from(texture: Texture2D) -> Self {
+        Self { texture }
+    }
+}
+
+impl SpriteSheet {
+    ///
+    /// Re-packs the given sprites of this sprite sheet tightly, for example to remove wasted transparent space from an existing sprite sheet.
+    /// Each sprite is trimmed to the texels with non-zero alpha (see [Texture2D::trim_transparent]) and then packed using an [AtlasBuilder].
+    ///
+    /// Returns the new sprite sheet texture together with the rectangle of the trimmed content of each sprite in the new sheet,
+    /// in the same order as the given sprites. A fully transparent sprite results in an empty rectangle.
+    ///
+    pub fn repack(&self, sprites: &[Rect]) -> (Texture2D, Vec<Rect>) {
+        let mut builder = AtlasBuilder::new();
+        for (i, sprite) in sprites.iter().enumerate() {
+            if let Some(sprite) = self
+                .texture
+                .crop(sprite.x, sprite.y, sprite.width, sprite.height)
+                .trim_transparent(0)
+            {
+                builder.add(i.to_string(), sprite);
+            }
+        }
+        let (sheet, uv_rects) = builder.build();
+        let (width, height) = (sheet.width as f32, sheet.height as f32);
+        let rects = (0..sprites.len())
+            .map(|i| {
+                uv_rects
+                    .get(&i.to_string())
+                    .map(|[u_min, v_min, u_max, v_max]| {
+                        let x = (u_min * width).round() as u32;
+                        let y = (v_min * height).round() as u32;
+                        Rect {
+                            x,
+                            y,
+                            width: (u_max * width).round() as u32 - x,
+                            height: (v_max * height).round() as u32 - y,
+                        }
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        (
+            Texture2D {
+                name: self.texture.name.clone(),
+                ..sheet
+            },
+            rects,
+        )
+    }
+}
+
+impl Texture2D {
+    ///
+    /// Splits this sprite sheet into the frames of a grid with the given number of columns and rows of equally sized cells.
+    /// The frames are returned in row-major order starting with the first texel of the [TextureData](crate::TextureData).
+    ///
+    /// Returns an [InvalidGrid](crate::Error::InvalidGrid) error if the width is not divisible by the number of columns or the height is not divisible by the number of rows.
+    ///
+    pub fn slice_grid(&self, cols: u32, rows: u32) -> crate::Result<Vec<Texture2D>> {
+        if cols == 0
+            || rows == 0
+            || self.width / cols * cols != self.width
+            || self.height / rows * rows != self.height
+        {
+            return Err(crate::Error::InvalidGrid(
+                cols,
+                rows,
+                self.width,
+                self.height,
+            ));
+        }
+        let (width, height) = (self.width / cols, self.height / rows);
+        Ok((0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (col, row)))
+            .map(|(col, row)| self.crop(col * width, row * height, width, height))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TextureData;
+
+    fn tex(width: u32, height: u32, color: [u8; 4]) -> Texture2D {
+        Texture2D {
+            data: TextureData::RgbaU8(vec![color; (width * height) as usize]),
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn pack() {
+        let mut builder = AtlasBuilder::new();
+        builder
+            .set_padding(2)
+            .add("red", tex(16, 8, [255, 0, 0, 255]))
+            .add("green", tex(5, 20, [0, 255, 0, 255]))
+            .add("blue", tex(10, 10, [0, 0, 255, 255]));
+        let (atlas, rects) = builder.build();
+        assert_eq!(rects.len(), 3);
+
+        let names = ["red", "green", "blue"];
+        for name in names {
+            let rect = rects[name];
+            assert!(rect.iter().all(|v| (0.0..=1.0).contains(v)));
+            assert!(rect[0] < rect[2] && rect[1] < rect[3]);
+        }
+        for a in names {
+            for b in names.iter().filter(|b| **b != a) {
+                let (ra, rb) = (rects[a], rects[*b]);
+                let overlap = ra[0] < rb[2] && rb[0] < ra[2] && ra[1] < rb[3] && rb[1] < ra[3];
+                assert!(!overlap, "{} and {} overlap", a, b);
+            }
+        }
+
+        let rect = rects["blue"];
+        let x = (rect[0] * atlas.width as f32) as u32;
+        let y = (rect[1] * atlas.height as f32) as u32;
+        if let TextureData::RgbaU8(data) = &atlas.data {
+            assert_eq!(data[(y * atlas.width + x) as usize], [0, 0, 255, 255]);
+            // Padding is filled with the edge texels.
+            assert_eq!(
+                data[((y - 1) * atlas.width + x - 1) as usize],
+                [0, 0, 255, 255]
+            );
+        } else {
+            panic!("Wrong texture data: {:?}", atlas.data)
+        }
+
+        // Empty textures are not packed.
+        let mut builder = AtlasBuilder::new();
+        builder
+            .set_padding(2)
+            .add("red", tex(4, 4, [255, 0, 0, 255]))
+            .add("empty", tex(0, 3, [0, 0, 0, 0]));
+        let (atlas, rects) = builder.build();
+        assert_eq!((atlas.width, atlas.height), (8, 8));
+        assert_eq!(rects["empty"], [0.0; 4]);
+    }
+
+    #[test]
+    pub fn repack() {
+        // Two 8x8 sprites side by side, each with a 2x2 opaque square in the middle.
+        let mut sheet = tex(16, 8, [0, 0, 0, 0]);
+        let red = tex(2, 2, [255, 0, 0, 255]);
+        let green = tex(2, 2, [0, 255, 0, 255]);
+        sheet.blit(&red, 3, 3);
+        sheet.blit(&green, 11, 3);
+
+        let rect = |x, y, width, height| Rect {
+            x,
+            y,
+            width,
+            height,
+        };
+        let (packed, rects) = SpriteSheet::from(sheet.clone()).repack(&[
+            rect(0, 0, 8, 8),
+            rect(8, 0, 8, 8),
+            rect(0, 0, 1, 1),
+        ]);
+        assert!(packed.width * packed.height < sheet.width * sheet.height);
+        assert_eq!(rects.len(), 3);
+        assert_eq!(rects[2], Rect::default());
+        for (rect, color) in rects[..2].iter().zip([[255, 0, 0, 255], [0, 255, 0, 255]]) {
+            assert_eq!((rect.width, rect.height), (2, 2));
+            let sprite = packed.crop(rect.x, rect.y, rect.width, rect.height);
+            assert_eq!(sprite.data, TextureData::RgbaU8(vec![color; 4]));
+        }
+    }
+
+    #[test]
+    pub fn slice_grid() {
+        // A 4x2 grid of 3x2 frames where the first texel of each frame holds the frame index.
+        let sheet = Texture2D {
+            data: TextureData::RU8(
+                (0..12 * 4)
+                    .map(|i| {
+                        let (x, y) = (i % 12, i / 12);
+                        if (x % 3, y % 2) == (0, 0) {
+                            (y / 2 * 4 + x / 3) as u8
+                        } else {
+                            255
+                        }
+                    })
+                    .collect(),
+            ),
+            width: 12,
+            height: 4,
+            ..Default::default()
+        };
+        let frames = sheet.slice_grid(4, 2).unwrap();
+        assert_eq!(frames.len(), 8);
+        for (i, frame) in frames.iter().enumerate() {
+            assert_eq!((frame.width, frame.height), (3, 2));
+            let TextureData::RU8(data) = &frame.data else {
+                panic!("expected RU8 data");
+            };
+            assert_eq!(data[0], i as u8);
+            assert!(data[1..].iter().all(|v| *v == 255));
+        }
+        assert!(sheet.slice_grid(5, 2).is_err());
+        assert!(sheet.slice_grid(4, 0).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use crate::texture::{premultiply, unpremultiply, Texture2D};
+
+///
+/// A color lookup table which maps RGB colors to other RGB colors, for example used for color grading.
+/// Can be loaded from the `.cube` format using [ColorLut::from_cube_bytes] and applied to a texture using [Texture2D::apply_lut].
+///
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorLut {
+    /// A curve for each of the red, green and blue channels.
+    /// The entries are evenly spaced over the `[0..1]` input range and the curve of a channel is the corresponding component of each entry.
+    OneDimensional(Vec<[f32; 3]>),
+    /// A lattice with `size` entries along each axis, so `size * size * size` entries in total, evenly spaced over the `[0..1]` input range.
+    /// The red index changes fastest, then the green and then the blue, ie. the entry for `(r, g, b)` is at `r + g * size + b * size * size`.
+    ThreeDimensional(usize, Vec<[f32; 3]>),
+}
+
+impl ColorLut {
+    ///
+    /// Parses a 1D or 3D lookup table in the `.cube` format.
+    /// Only the default `[0..1]` domain is supported.
+    ///
+    /// Returns a [CubeCorruptData](crate::Error::CubeCorruptData) error if the data is not a valid `.cube` file or if the number of entries does not match the size.
+    ///
+    pub fn from_cube_bytes(bytes: &[u8]) -> crate::Result<Self> {
+        let text = std::str::from_utf8(bytes).map_err(|_| crate::Error::CubeCorruptData)?;
+        let mut size_1d = None;
+        let mut size_3d = None;
+        let mut values = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let keyword = words.next().unwrap();
+            match keyword {
+                "TITLE" => {}
+                "LUT_1D_SIZE" => size_1d = Some(parse_size(words.next())?),
+                "LUT_3D_SIZE" => size_3d = Some(parse_size(words.next())?),
+                "DOMAIN_MIN" | "DOMAIN_MAX" => {
+                    let expected = if keyword == "DOMAIN_MIN" { 0.0 } else { 1.0 };
+                    if parse_entry(words)? != [expected; 3] {
+                        return Err(crate::Error::CubeCorruptData);
+                    }
+                }
+                _ => values.push(parse_entry(line.split_whitespace())?),
+            }
+        }
+        match (size_1d, size_3d) {
+            (Some(size), None) if size >= 2 && values.len() == size => {
+                Ok(Self::OneDimensional(values))
+            }
+            (None, Some(size))
+                if size >= 2
+                    && size.checked_mul(size).and_then(|s| s.checked_mul(size))
+                        == Some(values.len()) =>
+            {
+                Ok(Self::ThreeDimensional(size, values))
+            }
+            _ => Err(crate::Error::CubeCorruptData),
+        }
+    }
+
+    ///
+    /// Looks up the given RGB color, which is clamped to the `[0..1]` range.
+    /// A 1D table is linearly interpolated for each channel and a 3D table is trilinearly interpolated.
+    ///
+    pub fn lookup(&self, color: [f32; 3]) -> [f32; 3] {
+        match self {
+            Self::OneDimensional(values) => {
+                let mut result = [0.0; 3];
+                for (i, result) in result.iter_mut().enumerate() {
+                    let (i0, i1, t) = lattice_position(color[i], values.len());
+                    *result = values[i0][i] * (1.0 - t) + values[i1][i] * t;
+                }
+                result
+            }
+            Self::ThreeDimensional(size, values) => {
+                let (r0, r1, tr) = lattice_position(color[0], *size);
+                let (g0, g1, tg) = lattice_position(color[1], *size);
+                let (b0, b1, tb) = lattice_position(color[2], *size);
+                let entry = |r: usize, g: usize, b: usize| values[r + g * size + b * size * size];
+                let mut result = [0.0; 3];
+                for (i, result) in result.iter_mut().enumerate() {
+                    let lerp = |a: f32, b: f32, t: f32| a * (1.0 - t) + b * t;
+                    let c00 = lerp(entry(r0, g0, b0)[i], entry(r1, g0, b0)[i], tr);
+                    let c10 = lerp(entry(r0, g1, b0)[i], entry(r1, g1, b0)[i], tr);
+                    let c01 = lerp(entry(r0, g0, b1)[i], entry(r1, g0, b1)[i], tr);
+                    let c11 = lerp(entry(r0, g1, b1)[i], entry(r1, g1, b1)[i], tr);
+                    *result = lerp(lerp(c00, c10, tg), lerp(c01, c11, tg), tb);
+                }
+                result
+            }
+        }
+    }
+}
+
+impl Texture2D {
+    ///
+    /// Returns a copy of this texture where the color of each texel is mapped through the given lookup table.
+    /// The alpha channel is left unchanged and the result has the same [TextureData](crate::TextureData) format as this texture.
+    /// Premultiplied colors are unpremultiplied before the lookup.
+    ///
+    pub fn apply_lut(&self, lut: &ColorLut) -> Texture2D {
+        let data = self
+            .data
+            .same_format_from_rgba_f32(self.data.iter_rgba_f32().map(|c| {
+                let c = if self.premultiplied {
+                    unpremultiply(c)
+                } else {
+                    c
+                };
+                let [r, g, b] = lut.lookup([c[0], c[1], c[2]]);
+                if self.premultiplied {
+                    premultiply([r, g, b, c[3]])
+                } else {
+                    [r, g, b, c[3]]
+                }
+            }));
+        Texture2D {
+            data,
+            ..self.clone()
+        }
+    }
+}
+
+fn parse_size(word: Option<&str>) -> crate::Result<usize> {
+    word.and_then(|w| w.parse().ok())
+        .ok_or(crate::Error::CubeCorruptData)
+}
+
+fn parse_entry<'a>(mut words: impl Iterator<Item = &'a str>) -> crate::Result<[f32; 3]> {
+    let mut entry = [0.0; 3];
+    for value in entry.iter_mut() {
+        *value = words
+            .next()
+            .and_then(|w| w.parse().ok())
+            .ok_or(crate::Error::CubeCorruptData)?;
+    }
+    Ok(entry)
+}
+
+///
+/// Returns the two closest lattice indices and the interpolation factor between them for the given value in the `[0..1]` range.
+///
+fn lattice_position(value: f32, size: usize) -> (usize, usize, f32) {
+    let position = value.clamp(0.0, 1.0) * (size - 1) as f32;
+    let i0 = (position.floor() as usize).min(size - 2);
+    (i0, i0 + 1, position - i0 as f32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TextureData;
+
+    fn tex() -> Texture2D {
+        Texture2D {
+            data: TextureData::RgbaU8(vec![
+                [0, 0, 0, 255],
+                [255, 128, 0, 255],
+                [10, 200, 60, 128],
+                [255, 255, 255, 0],
+            ]),
+            width: 2,
+            height: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn identity() {
+        let lut = ColorLut::from_cube_bytes(
+            b"TITLE \"identity\"\n# comment\nLUT_3D_SIZE 2\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 1 1 1\n\
+            0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1\n",
+        )
+        .unwrap();
+        assert!(matches!(lut, ColorLut::ThreeDimensional(2, _)));
+        assert_eq!(tex().apply_lut(&lut).data, tex().data);
+    }
+
+    #[test]
+    pub fn inversion() {
+        let expected = TextureData::RgbaU8(vec![
+            [255, 255, 255, 255],
+            [0, 127, 255, 255],
+            [245, 55, 195, 128],
+            [0, 0, 0, 0],
+        ]);
+
+        let lut = ColorLut::from_cube_bytes(b"LUT_1D_SIZE 2\n1 1 1\n0 0 0\n").unwrap();
+        assert_eq!(tex().apply_lut(&lut).data, expected);
+
+        let lut = ColorLut::from_cube_bytes(
+            b"LUT_3D_SIZE 2\n1 1 1\n0 1 1\n1 0 1\n0 0 1\n1 1 0\n0 1 0\n1 0 0\n0 0 0\n",
+        )
+        .unwrap();
+        assert_eq!(tex().apply_lut(&lut).data, expected);
+    }
+
+    #[test]
+    pub fn corrupt() {
+        assert!(ColorLut::from_cube_bytes(b"LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        // The number of entries of this size overflows.
+        assert!(ColorLut::from_cube_bytes(b"LUT_3D_SIZE 4194304\n0 0 0\n").is_err());
+        assert!(ColorLut::from_cube_bytes(b"LUT_1D_SIZE 2\n0 0\n1 1 1\n").is_err());
+    }
+}
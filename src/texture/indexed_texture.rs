@@ -0,0 +1,219 @@
+use crate::texture::{Texture2D, TextureData};
+
+///
+/// A CPU-side version of a 2D texture where each pixel is an index into a palette of at most 256 colors.
+/// Can be constructed from a [Texture2D] using [Texture2D::to_indexed].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexedTexture {
+    /// The 8-bit RGBA colors that the indices refer to.
+    pub palette: Vec<[u8; 4]>,
+    /// An index into the palette for each pixel, in the same order as the [TextureData] of a [Texture2D].
+    pub indices: Vec<u8>,
+    /// The width of the image
+    pub width: u32,
+    /// The height of the image
+    pub height: u32,
+}
+
+impl IndexedTexture {
+    ///
+    /// Returns a [Texture2D] with [TextureData::RgbaU8] data where each pixel is the palette color of the corresponding index.
+    ///
+    pub fn to_texture2d(&self) -> Texture2D {
+        Texture2D {
+            data: TextureData::RgbaU8(
+                self.indices
+                    .iter()
+                    .map(|i| self.palette[*i as usize])
+                    .collect(),
+            ),
+            width: self.width,
+            height: self.height,
+            ..Default::default()
+        }
+    }
+}
+
+impl Texture2D {
+    ///
+    /// Converts this texture to an [IndexedTexture] with a palette of at most `max_colors` colors, which is capped at 256.
+    /// The palette is found using [Texture2D::extract_palette] and each pixel is mapped to the nearest color in the palette.
+    /// An empty texture results in an empty palette.
+    ///
+    pub fn to_indexed(&self, max_colors: usize) -> IndexedTexture {
+        let palette = self.extract_palette(max_colors.min(256));
+        let indices = self
+            .rgba_u8_texels()
+            .map(|color| nearest_palette_index(&palette, color) as u8)
+            .collect();
+        IndexedTexture {
+            palette,
+            indices,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture with [TextureData::RgbaU8] data where each texel is replaced by the nearest color in the given fixed palette,
+    /// for example the colors of a retro console. In contrast to [Texture2D::to_indexed], the palette is not derived from the texture.
+    /// If `dither` is true, the quantization error of each texel is distributed to the neighbouring texels using Floyd-Steinberg error diffusion,
+    /// which preserves the average color of larger areas at the cost of noise.
+    /// An empty palette returns an unchanged copy of this texture.
+    ///
+    pub fn quantize_to_palette(&self, palette: &[[u8; 4]], dither: bool) -> Texture2D {
+        if palette.is_empty() {
+            return self.clone();
+        }
+        let width = self.width as usize;
+        let mut texels: Vec<[f32; 4]> =
+            self.rgba_u8_texels().map(|c| c.map(|v| v as f32)).collect();
+        let mut result = Vec::with_capacity(texels.len());
+        for i in 0..texels.len() {
+            let color = texels[i].map(|v| v.round().clamp(0.0, 255.0) as u8);
+            let quantized = palette[nearest_palette_index(palette, color)];
+            result.push(quantized);
+            if dither {
+                let error: [f32; 4] = [0, 1, 2, 3].map(|c| texels[i][c] - quantized[c] as f32);
+                let x = i % width;
+                let mut diffuse = |index: usize, weight: f32| {
+                    if let Some(texel) = texels.get_mut(index) {
+                        (0..4).for_each(|c| texel[c] += error[c] * weight);
+                    }
+                };
+                if x + 1 < width {
+                    diffuse(i + 1, 7.0 / 16.0);
+                    diffuse(i + width + 1, 1.0 / 16.0);
+                }
+                if x > 0 {
+                    diffuse(i + width - 1, 3.0 / 16.0);
+                }
+                diffuse(i + width, 5.0 / 16.0);
+            }
+        }
+        Texture2D {
+            data: TextureData::RgbaU8(result),
+            ..self.clone()
+        }
+    }
+}
+
+///
+/// Returns the index of the palette color with the smallest euclidean distance to the given color.
+///
+pub(crate) fn nearest_palette_index(palette: &[[u8; 4]], color: [u8; 4]) -> usize {
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, p)| {
+            (0..4)
+                .map(|i| {
+                    let d = p[i] as i32 - color[i] as i32;
+                    d * d
+                })
+                .sum::<i32>()
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn indexed_round_trip() {
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![
+                [250, 0, 0, 255],
+                [255, 0, 0, 255],
+                [0, 0, 255, 255],
+                [0, 0, 250, 255],
+                [0, 255, 0, 255],
+                [0, 250, 0, 255],
+            ]),
+            width: 3,
+            height: 2,
+            ..Default::default()
+        };
+        let indexed = tex.to_indexed(3);
+        assert_eq!(indexed.palette.len(), 3);
+        assert_eq!(indexed.indices.len(), 6);
+
+        let reconstructed = tex.to_indexed(3).to_texture2d();
+        assert_eq!(reconstructed.width, 3);
+        assert_eq!(reconstructed.height, 2);
+        if let (TextureData::RgbaU8(original), TextureData::RgbaU8(data)) =
+            (&tex.data, &reconstructed.data)
+        {
+            for (color, reconstructed_color) in original.iter().zip(data.iter()) {
+                let nearest = indexed.palette[nearest_palette_index(&indexed.palette, *color)];
+                assert_eq!(*reconstructed_color, nearest);
+            }
+        } else {
+            panic!("Wrong texture data: {:?}", reconstructed.data)
+        }
+
+        let exact = tex.to_indexed(256).to_texture2d();
+        assert_eq!(exact.data, tex.data);
+
+        let empty = Texture2D {
+            data: TextureData::RgbaU8(Vec::new()),
+            width: 0,
+            height: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            empty.to_indexed(16),
+            IndexedTexture {
+                palette: Vec::new(),
+                indices: Vec::new(),
+                width: 0,
+                height: 0,
+            }
+        );
+    }
+
+    #[test]
+    pub fn quantize_to_palette() {
+        let palette = [
+            [0, 0, 0, 255],
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [255, 255, 255, 255],
+        ];
+        let tex = Texture2D {
+            data: TextureData::RgbU8(
+                (0..16 * 16)
+                    .map(|i| [(i % 16 * 17) as u8, (i / 16 * 17) as u8, 128])
+                    .collect(),
+            ),
+            width: 16,
+            height: 16,
+            ..Default::default()
+        };
+        for dither in [false, true] {
+            let quantized = tex.quantize_to_palette(&palette, dither);
+            assert_eq!((quantized.width, quantized.height), (16, 16));
+            let TextureData::RgbaU8(data) = quantized.data else {
+                panic!("expected RgbaU8 data");
+            };
+            assert!(data.iter().all(|c| palette.contains(c)));
+        }
+
+        // Dithering a mid gray with black and white keeps the average close to the gray.
+        let gray = Texture2D {
+            data: TextureData::RU8(vec![100; 32 * 32]),
+            width: 32,
+            height: 32,
+            ..Default::default()
+        };
+        let palette = [[0, 0, 0, 255], [255, 255, 255, 255]];
+        let average = |tex: &Texture2D| tex.average_color(false)[0] * 255.0;
+        assert_eq!(average(&gray.quantize_to_palette(&palette, false)), 0.0);
+        assert!((average(&gray.quantize_to_palette(&palette, true)) - 100.0).abs() < 4.0);
+        assert_eq!(gray.quantize_to_palette(&[], true), gray);
+    }
+}
@@ -1,3 +1,8 @@
+use crate::prelude::{linear_to_srgb, srgb_to_linear};
+use crate::texture::{
+    premultiply, unpremultiply, DownsampleKernel, Endianness, TextureFormat, TextureOrientation,
+    YuvMatrix,
+};
 #[doc(inline)]
 pub use crate::texture::{Interpolation, TextureData, Wrapping};
 
@@ -20,12 +25,15 @@ pub struct Texture2D {
     /// The way the pixel data is interpolated when the texture is close
     pub mag_filter: Interpolation,
     /// Specifies whether mipmaps should be created for this texture and what type of interpolation to use between the two closest mipmaps.
-    /// Note, however, that the mipmaps only will be created if the width and height of the texture are power of two.
+    /// Note, however, that the mipmaps only will be created if the width and height of the texture are power of two, see [Texture2D::mipmaps_supported].
     pub mip_map_filter: Option<Interpolation>,
     /// Determines how the texture is sampled outside the [0..1] s coordinate range (the first value of the uv coordinates).
     pub wrap_s: Wrapping,
     /// Determines how the texture is sampled outside the [0..1] t coordinate range (the second value of the uv coordinates).
     pub wrap_t: Wrapping,
+    /// Whether or not the color channels of the pixel data are premultiplied with the alpha channel.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub premultiplied: bool,
 }
 
 impl Default for Texture2D {
@@ -40,6 +48,3718 @@ impl Default for Texture2D {
             mip_map_filter: Some(Interpolation::Linear),
             wrap_s: Wrapping::Repeat,
             wrap_t: Wrapping::Repeat,
+            premultiplied: false,
         }
     }
 }
+
+///
+/// Sampling options that can be applied to a [Texture2D], for example when deserializing it using [RawAssets::deserialize_texture_with_options](crate::io::RawAssets::deserialize_texture_with_options).
+/// The default options are the same as the defaults of [Texture2D].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextureOptions {
+    /// See [Texture2D::min_filter].
+    pub min_filter: Interpolation,
+    /// See [Texture2D::mag_filter].
+    pub mag_filter: Interpolation,
+    /// See [Texture2D::mip_map_filter].
+    pub mip_map_filter: Option<Interpolation>,
+    /// See [Texture2D::wrap_s].
+    pub wrap_s: Wrapping,
+    /// See [Texture2D::wrap_t].
+    pub wrap_t: Wrapping,
+    /// Whether the color channels of the source image are already multiplied with the alpha channel, as is the case for some PNG and APNG files.
+    /// This only sets [Texture2D::premultiplied] and does not change the texel values, use [Texture2D::unpremultiply_alpha] to convert to straight alpha.
+    pub premultiplied: bool,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        let texture = Texture2D::default();
+        Self {
+            min_filter: texture.min_filter,
+            mag_filter: texture.mag_filter,
+            mip_map_filter: texture.mip_map_filter,
+            wrap_s: texture.wrap_s,
+            wrap_t: texture.wrap_t,
+            premultiplied: texture.premultiplied,
+        }
+    }
+}
+
+impl Texture2D {
+    ///
+    /// Constructs a texture with [TextureData::RgbaU8] data from raw RGBA bytes, for example read back from a framebuffer,
+    /// without copying or converting the bytes when possible.
+    ///
+    /// Returns an [InvalidBufferLength](crate::Error::InvalidBufferLength) error if the number of bytes is not exactly `width * height * 4`.
+    /// Use [Texture2D::from_raw_rgba_with_stride] if the rows are padded.
+    ///
+    pub fn from_raw_rgba(width: u32, height: u32, bytes: Vec<u8>) -> crate::Result<Self> {
+        Self::from_raw_rgba_with_stride(width, height, width as usize * 4, bytes)
+    }
+
+    ///
+    /// Constructs a texture with [TextureData::RgbaU8] data from raw RGBA bytes where each row starts `stride` bytes after the previous one,
+    /// as for example produced by BMP decoders which align each row to 4 bytes. The padding at the end of each row is removed.
+    ///
+    /// Returns an [InvalidBufferLength](crate::Error::InvalidBufferLength) error if the stride is less than `width * 4`
+    /// or the number of bytes is not exactly `stride * height`.
+    ///
+    pub fn from_raw_rgba_with_stride(
+        width: u32,
+        height: u32,
+        stride: usize,
+        bytes: Vec<u8>,
+    ) -> crate::Result<Self> {
+        Ok(Self::from_raw(
+            width,
+            height,
+            TextureData::RgbaU8(cast_texels(width, height, stride, bytes)?),
+        ))
+    }
+
+    ///
+    /// Constructs a texture with [TextureData::RgbU8] data from raw RGB bytes.
+    /// Otherwise the same as [Texture2D::from_raw_rgba].
+    ///
+    pub fn from_raw_rgb(width: u32, height: u32, bytes: Vec<u8>) -> crate::Result<Self> {
+        Self::from_raw_rgb_with_stride(width, height, width as usize * 3, bytes)
+    }
+
+    ///
+    /// Constructs a texture with [TextureData::RgbU8] data from raw RGB bytes with padded rows.
+    /// Otherwise the same as [Texture2D::from_raw_rgba_with_stride].
+    ///
+    pub fn from_raw_rgb_with_stride(
+        width: u32,
+        height: u32,
+        stride: usize,
+        bytes: Vec<u8>,
+    ) -> crate::Result<Self> {
+        Ok(Self::from_raw(
+            width,
+            height,
+            TextureData::RgbU8(cast_texels(width, height, stride, bytes)?),
+        ))
+    }
+
+    ///
+    /// Constructs a texture with [TextureData::RgbaF32] data from raw RGBA float values.
+    /// Otherwise the same as [Texture2D::from_raw_rgba].
+    ///
+    pub fn from_raw_rgba_f32(width: u32, height: u32, values: Vec<f32>) -> crate::Result<Self> {
+        Ok(Self::from_raw(
+            width,
+            height,
+            TextureData::RgbaF32(cast_texels(width, height, width as usize * 4, values)?),
+        ))
+    }
+
+    ///
+    /// Constructs a texture with the given format from raw, tightly packed bytes, for example read from a headerless 16-bit or float image file,
+    /// where multi-byte channel values are read with the given byte order, see [TextureData::from_bytes].
+    ///
+    /// Returns an [InvalidBufferLength](crate::Error::InvalidBufferLength) error if the number of bytes does not match the width, height and format.
+    ///
+    pub fn from_raw_bytes(
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        bytes: &[u8],
+        endianness: Endianness,
+    ) -> crate::Result<Self> {
+        let expected = width as usize
+            * height as usize
+            * (format.channel_count() * format.bytes_per_channel()) as usize;
+        if bytes.len() != expected {
+            return Err(crate::Error::InvalidBufferLength(
+                "texture".to_owned(),
+                expected,
+                bytes.len(),
+            ));
+        }
+        Ok(Self::from_raw(
+            width,
+            height,
+            TextureData::from_bytes(format, bytes, endianness),
+        ))
+    }
+
+    ///
+    /// Constructs an opaque texture with [TextureData::RgbaU8] data from planar YUV 4:2:0 video data with limited (video) range, as delivered by most video decoders.
+    /// The `y` plane has a sample for each texel, while the `u` and `v` planes have a sample for each 2x2 block of texels,
+    /// ie. `((width + 1) / 2) * ((height + 1) / 2)` samples, which is used for all four texels of the block.
+    /// The colors are converted to RGB using the given [YuvMatrix].
+    ///
+    /// Returns an [InvalidBufferLength](crate::Error::InvalidBufferLength) error if a plane does not have the expected number of samples.
+    ///
+    pub fn from_yuv420(
+        y: &[u8],
+        u: &[u8],
+        v: &[u8],
+        width: u32,
+        height: u32,
+        matrix: YuvMatrix,
+    ) -> crate::Result<Self> {
+        let (width, height) = (width as usize, height as usize);
+        let chroma_width = width.div_ceil(2);
+        for (name, plane, expected) in [
+            ("y", y, width * height),
+            ("u", u, chroma_width * height.div_ceil(2)),
+            ("v", v, chroma_width * height.div_ceil(2)),
+        ] {
+            if plane.len() != expected {
+                return Err(crate::Error::InvalidBufferLength(
+                    name.to_owned(),
+                    expected,
+                    plane.len(),
+                ));
+            }
+        }
+        let (kr, kb) = match matrix {
+            YuvMatrix::Bt601 => (0.299, 0.114),
+            YuvMatrix::Bt709 => (0.2126, 0.0722),
+        };
+        let kg = 1.0 - kr - kb;
+        let data = (0..width * height)
+            .map(|i| {
+                let (x, row) = (i % width, i / width);
+                let chroma = row / 2 * chroma_width + x / 2;
+                let luma = (y[i] as f32 - 16.0) * 255.0 / 219.0;
+                let cb = (u[chroma] as f32 - 128.0) * 255.0 / 224.0;
+                let cr = (v[chroma] as f32 - 128.0) * 255.0 / 224.0;
+                let to_u8 = |c: f32| c.round().clamp(0.0, 255.0) as u8;
+                [
+                    to_u8(luma + 2.0 * (1.0 - kr) * cr),
+                    to_u8(luma - 2.0 * kb * (1.0 - kb) / kg * cb - 2.0 * kr * (1.0 - kr) / kg * cr),
+                    to_u8(luma + 2.0 * (1.0 - kb) * cb),
+                    255,
+                ]
+            })
+            .collect();
+        Ok(Self::from_raw(
+            width as u32,
+            height as u32,
+            TextureData::RgbaU8(data),
+        ))
+    }
+
+    ///
+    /// Constructs a texture with [TextureData::RgbaU8] data by evaluating the given function for the `x` and `y` pixel position of each texel,
+    /// for example to generate procedural textures or test data. The texels are evaluated row by row starting with the first texel of the [TextureData].
+    ///
+    /// ```
+    /// # use three_d_asset::*;
+    /// let gradient = Texture2D::from_fn(256, 1, |x, _| [x as u8, x as u8, x as u8, 255]);
+    /// ```
+    ///
+    pub fn from_fn<F: Fn(u32, u32) -> [u8; 4]>(width: u32, height: u32, f: F) -> Self {
+        Self::from_raw(
+            width,
+            height,
+            TextureData::RgbaU8(
+                (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (x, y)))
+                    .map(|(x, y)| f(x, y))
+                    .collect(),
+            ),
+        )
+    }
+
+    ///
+    /// Constructs a texture with [TextureData::RgbaF32] data by evaluating the given function for the `x` and `y` pixel position of each texel.
+    /// Otherwise the same as [Texture2D::from_fn].
+    ///
+    pub fn from_fn_f32<F: Fn(u32, u32) -> [f32; 4]>(width: u32, height: u32, f: F) -> Self {
+        Self::from_raw(
+            width,
+            height,
+            TextureData::RgbaF32(
+                (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (x, y)))
+                    .map(|(x, y)| f(x, y))
+                    .collect(),
+            ),
+        )
+    }
+
+    fn from_raw(width: u32, height: u32, data: TextureData) -> Self {
+        Texture2D {
+            data,
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Returns the width and height of this texture.
+    ///
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    ///
+    /// Returns a `size` times `size` texture with a magenta and black checkerboard pattern of 8 times 8 cells,
+    /// which is easy to spot and therefore useful as a substitute for a texture that failed to load,
+    /// see [RawAssets::deserialize_texture_or_fallback](crate::io::RawAssets::deserialize_texture_or_fallback).
+    /// The texture uses nearest interpolation and no mipmaps to keep the pattern sharp.
+    ///
+    pub fn missing_texture(size: u32) -> Texture2D {
+        let cell_size = (size / 8).max(1);
+        let data = (0..size * size)
+            .map(|i| {
+                if (i % size / cell_size + i / size / cell_size) & 1 == 0 {
+                    [255, 0, 255, 255]
+                } else {
+                    [0, 0, 0, 255]
+                }
+            })
+            .collect();
+        Texture2D {
+            name: "missing".to_owned(),
+            data: TextureData::RgbaU8(data),
+            width: size,
+            height: size,
+            min_filter: Interpolation::Nearest,
+            mag_filter: Interpolation::Nearest,
+            mip_map_filter: None,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Sets the filters, wrapping modes and [Texture2D::premultiplied] flag of this texture to the ones in the given options.
+    ///
+    pub fn apply_options(&mut self, options: &TextureOptions) {
+        self.min_filter = options.min_filter;
+        self.mag_filter = options.mag_filter;
+        self.mip_map_filter = options.mip_map_filter;
+        self.wrap_s = options.wrap_s;
+        self.wrap_t = options.wrap_t;
+        self.premultiplied = options.premultiplied;
+    }
+
+    ///
+    /// Returns this texture with the given [Texture2D::wrap_s] and [Texture2D::wrap_t] wrapping modes, which is useful for chained configuration after construction.
+    ///
+    /// ```
+    /// # use three_d_asset::*;
+    /// let texture = Texture2D::default()
+    ///     .with_wrapping(Wrapping::ClampToEdge, Wrapping::ClampToEdge)
+    ///     .with_filters(Interpolation::Nearest, Interpolation::Nearest)
+    ///     .with_mipmaps(None);
+    /// ```
+    ///
+    pub fn with_wrapping(mut self, wrap_s: Wrapping, wrap_t: Wrapping) -> Self {
+        self.wrap_s = wrap_s;
+        self.wrap_t = wrap_t;
+        self
+    }
+
+    ///
+    /// Returns this texture with the given [Texture2D::min_filter] and [Texture2D::mag_filter], see [Texture2D::with_wrapping].
+    ///
+    pub fn with_filters(mut self, min_filter: Interpolation, mag_filter: Interpolation) -> Self {
+        self.min_filter = min_filter;
+        self.mag_filter = mag_filter;
+        self
+    }
+
+    ///
+    /// Returns this texture with the given [Texture2D::mip_map_filter], see [Texture2D::with_wrapping].
+    ///
+    pub fn with_mipmaps(mut self, mip_map_filter: Option<Interpolation>) -> Self {
+        self.mip_map_filter = mip_map_filter;
+        self
+    }
+
+    ///
+    /// Multiplies the color channels with the alpha channel and marks the texture as [Texture2D::premultiplied].
+    /// Does nothing if the texture is already premultiplied.
+    ///
+    pub fn premultiply_alpha(&mut self) {
+        if !self.premultiplied {
+            if self.data.has_alpha() {
+                self.data = self
+                    .data
+                    .same_format_from_rgba_f32(self.data.iter_rgba_f32().map(premultiply));
+            }
+            self.premultiplied = true;
+        }
+    }
+
+    ///
+    /// Sets the color channels of all fully transparent texels to zero, without changing any other texels or the [Texture2D::premultiplied] flag.
+    /// Use this for premultiplied atlases, where the color of a transparent texel must be zero for linear interpolation to be correct,
+    /// when the texture is already premultiplied or should stay unpremultiplied, for example because the colors were edited after premultiplying.
+    /// For textures that are not premultiplied, extending the colors of the opaque texels into the transparent texels is usually better,
+    /// since the transparent texels then do not darken the edges when interpolated.
+    /// Only [TextureData::RgbaU8], [TextureData::RgbaU16], [TextureData::RgbaF16] and [TextureData::RgbaF32] data is changed.
+    ///
+    pub fn zero_transparent_rgb(&mut self) {
+        macro_rules! zero {
+            ($values:expr, $zero:expr) => {
+                for texel in $values.iter_mut().filter(|texel| texel[3] == $zero) {
+                    texel[..3].fill($zero);
+                }
+            };
+        }
+        match &mut self.data {
+            TextureData::RgbaU8(values) => zero!(values, 0),
+            TextureData::RgbaU16(values) => zero!(values, 0),
+            TextureData::RgbaF16(values) => zero!(values, crate::f16::ZERO),
+            TextureData::RgbaF32(values) => zero!(values, 0.0),
+            _ => {}
+        }
+    }
+
+    ///
+    /// Divides the color channels with the alpha channel and marks the texture as not [Texture2D::premultiplied].
+    /// Does nothing if the texture is not premultiplied.
+    ///
+    pub fn unpremultiply_alpha(&mut self) {
+        if self.premultiplied {
+            if self.data.has_alpha() {
+                self.data = self
+                    .data
+                    .same_format_from_rgba_f32(self.data.iter_rgba_f32().map(unpremultiply));
+            }
+            self.premultiplied = false;
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture with premultiplied [TextureData::RgbaF16] data, for example for compositing HDR UI layers.
+    /// The channel expansion, alpha premultiplication and conversion to half precision floats is done in a single pass.
+    /// The colors are not premultiplied again if this texture is already [Texture2D::premultiplied].
+    ///
+    pub fn to_premultiplied_rgba_f16(&self) -> Texture2D {
+        let data = self
+            .data
+            .iter_rgba_f32()
+            .map(|c| {
+                let c = if self.premultiplied {
+                    c
+                } else {
+                    premultiply(c)
+                };
+                c.map(crate::f16::from_f32)
+            })
+            .collect();
+        Texture2D {
+            data: TextureData::RgbaF16(data),
+            premultiplied: true,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture with the [TextureData] variant given by the target format, converting the channels and precision in a single pass.
+    /// The channels are converted via normalized RGBA values like [TextureData::iter_rgba_f32],
+    /// so one and two channel data is treated as luminance and alpha, a missing alpha channel is `1.0` and the red channel is used as luminance.
+    /// Values are clamped to the `[0..1]` range when converting to 8-bit or 16-bit data.
+    /// If the target format has no alpha channel, the colors of a [Texture2D::premultiplied] texture are kept as they are and the result is not marked as premultiplied.
+    ///
+    pub fn convert(&self, target: TextureFormat) -> Texture2D {
+        // An empty buffer only selects the target variant.
+        let data = TextureData::from_bytes(target, &[], Endianness::Little)
+            .same_format_from_rgba_f32(self.data.iter_rgba_f32());
+        Texture2D {
+            premultiplied: self.premultiplied && data.has_alpha(),
+            data,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture with [TextureData::RgbF16] data, for example to upload high dynamic range color as an RGB16F texture.
+    /// Grayscale data is broadcast to red, green and blue and the alpha channel is dropped.
+    /// The colors of a [Texture2D::premultiplied] texture are kept as they are, which is the same as compositing it onto black,
+    /// and the result is not marked as premultiplied since it has no alpha channel.
+    ///
+    pub fn to_rgb_f16(&self) -> Texture2D {
+        let data = self
+            .data
+            .iter_rgba_f32()
+            .map(|c| [c[0], c[1], c[2]].map(crate::f16::from_f32))
+            .collect();
+        Texture2D {
+            data: TextureData::RgbF16(data),
+            premultiplied: false,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture with [TextureData::RgbaU8] data where an ordered dither, using a 4x4 Bayer matrix,
+    /// is added to the normalized values before they are rounded to 8-bit, which reduces visible banding in smooth gradients,
+    /// for example when exporting high dynamic range or float textures. Values outside the `[0..1]` range are clamped.
+    ///
+    pub fn to_rgba_u8_dithered(&self) -> Texture2D {
+        const BAYER: [[f32; 4]; 4] = [
+            [0.0, 8.0, 2.0, 10.0],
+            [12.0, 4.0, 14.0, 6.0],
+            [3.0, 11.0, 1.0, 9.0],
+            [15.0, 7.0, 13.0, 5.0],
+        ];
+        let data = self
+            .data
+            .iter_rgba_f32()
+            .enumerate()
+            .map(|(i, c)| {
+                let (x, y) = (i % self.width as usize, i / self.width as usize);
+                let offset = (BAYER[y % 4][x % 4] + 0.5) / 16.0 - 0.5;
+                c.map(|v| (v * 255.0 + offset).round().clamp(0.0, 255.0) as u8)
+            })
+            .collect();
+        Texture2D {
+            data: TextureData::RgbaU8(data),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Samples the texture at the given uv coordinates and returns the normalized RGBA value.
+    /// The texture is sampled using the [Texture2D::mag_filter] interpolation and the [Texture2D::wrap_s] and [Texture2D::wrap_t] wrapping modes.
+    ///
+    /// The interpolation is always done on premultiplied colors to avoid color fringing at the edges of transparent areas.
+    /// The returned value is premultiplied if, and only if, the texture is [Texture2D::premultiplied].
+    /// A texture with zero width or height always returns transparent black.
+    ///
+    pub fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+        let x = u * self.width as f32 - 0.5;
+        let y = v * self.height as f32 - 0.5;
+        let color = self.interpolate(x, y, self.mag_filter, self.wrap_s, self.wrap_t);
+        if self.premultiplied {
+            color
+        } else {
+            unpremultiply(color)
+        }
+    }
+
+    ///
+    /// Samples the texture at the given uv coordinates like [Texture2D::sample], except that coordinates outside the texture return the given border color
+    /// instead of applying the [Texture2D::wrap_s] and [Texture2D::wrap_t] wrapping modes.
+    ///
+    /// The boundary behavior is exact: if both `u` and `v` are in the closed `[0..1]` range, the texture is sampled normally,
+    /// otherwise, including for NaN coordinates, the border color is returned as is.
+    /// Within the half texel closest to an edge, the texture is interpolated with [Wrapping::ClampToEdge],
+    /// so `u = 0.0` and `u = 1.0` return the color of the edge texels without any contribution from the border color.
+    /// This differs from the border clamping on the GPU, which blends the border color into the outer half texel.
+    ///
+    pub fn sample_with_border(&self, u: f32, v: f32, border_color: [f32; 4]) -> [f32; 4] {
+        if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+            return border_color;
+        }
+        let x = u * self.width as f32 - 0.5;
+        let y = v * self.height as f32 - 0.5;
+        let color = self.interpolate(
+            x,
+            y,
+            self.mag_filter,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        if self.premultiplied {
+            color
+        } else {
+            unpremultiply(color)
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture rotated by the given angle in radians about its center, counterclockwise as seen on screen,
+    /// for example for sprite and decal tooling. Use [Texture2D::reorient] for exact rotations by multiples of 90 degrees.
+    /// The result is enlarged to contain the whole rotated texture and the exposed areas are filled with the given background color.
+    /// The texture is resampled with the given interpolation on premultiplied colors in the same way as [Texture2D::sample_with_border].
+    ///
+    pub fn rotate(&self, radians: f32, filter: Interpolation, background: [f32; 4]) -> Texture2D {
+        let (sin, cos) = radians.sin_cos();
+        let (w, h) = (self.width as f32, self.height as f32);
+        // The small tolerance avoids an extra row or column due to rounding errors, for example for 90 degrees.
+        let width = ((w * cos.abs() + h * sin.abs()) - 1e-3).ceil().max(1.0) as u32;
+        let height = ((w * sin.abs() + h * cos.abs()) - 1e-3).ceil().max(1.0) as u32;
+        let background = if self.premultiplied {
+            premultiply(background)
+        } else {
+            background
+        };
+        let texels = (0..height).flat_map(|y| {
+            (0..width).map(move |x| {
+                let dx = x as f32 + 0.5 - width as f32 / 2.0;
+                let dy = y as f32 + 0.5 - height as f32 / 2.0;
+                let sx = cos * dx - sin * dy + w / 2.0;
+                let sy = sin * dx + cos * dy + h / 2.0;
+                if !(0.0..=w).contains(&sx) || !(0.0..=h).contains(&sy) {
+                    return background;
+                }
+                let color = self.interpolate(
+                    sx - 0.5,
+                    sy - 0.5,
+                    filter,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                );
+                if self.premultiplied {
+                    color
+                } else {
+                    unpremultiply(color)
+                }
+            })
+        });
+        Texture2D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            width,
+            height,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture scaled down, preserving the aspect ratio, so that it fits within the given maximum width and height,
+    /// for example to create a thumbnail. The texture is only scaled up to fill the bounds if `allow_upscale` is true.
+    /// The width and height of the result are rounded to the nearest integer and are at least one.
+    ///
+    pub fn resize_to_fit(
+        &self,
+        max_width: u32,
+        max_height: u32,
+        filter: Interpolation,
+        allow_upscale: bool,
+    ) -> Texture2D {
+        let scale =
+            (max_width as f32 / self.width as f32).min(max_height as f32 / self.height as f32);
+        if scale == 1.0 || (scale > 1.0 && !allow_upscale) {
+            return self.clone();
+        }
+        let width = ((self.width as f32 * scale).round() as u32).clamp(1, max_width.max(1));
+        let height = ((self.height as f32 * scale).round() as u32).clamp(1, max_height.max(1));
+        self.resize(width, height, filter)
+    }
+
+    ///
+    /// Returns a cheap thumbnail of this texture, for example for an asset browser, where the longer dimension is scaled to the given size
+    /// and the aspect ratio is preserved. In contrast to [Texture2D::resize_to_fit] with linear interpolation, every texel of this texture
+    /// contributes to the result, since each thumbnail texel is the average of the block of texels it covers, computed in a single pass.
+    /// The texture is never scaled up and the width and height of the result are at least one, unless this texture is empty,
+    /// in which case an unchanged copy is returned.
+    ///
+    pub fn thumbnail(&self, max_long_edge: u32) -> Texture2D {
+        let scale = max_long_edge as f32 / self.width.max(self.height) as f32;
+        if scale >= 1.0 || self.width == 0 || self.height == 0 {
+            return self.clone();
+        }
+        let width = ((self.width as f32 * scale).round() as u32).max(1);
+        let height = ((self.height as f32 * scale).round() as u32).max(1);
+        let range = |i: u32, size: u32, source_size: u32| {
+            let step = source_size as f32 / size as f32;
+            let start = ((i as f32 * step) as u32).min(source_size - 1);
+            let end = (((i + 1) as f32 * step) as u32).clamp(start + 1, source_size);
+            start..end
+        };
+        let texels = (0..height).flat_map(|y| {
+            (0..width).map(move |x| {
+                let (xs, ys) = (range(x, width, self.width), range(y, height, self.height));
+                let count = (xs.len() * ys.len()) as f32;
+                let mut sum = [0.0; 4];
+                for sy in ys {
+                    for sx in xs.clone() {
+                        let texel = self.premultiplied_texel_at((sy * self.width + sx) as usize);
+                        (0..4).for_each(|c| sum[c] += texel[c]);
+                    }
+                }
+                let color = sum.map(|v| v / count);
+                if self.premultiplied {
+                    color
+                } else {
+                    unpremultiply(color)
+                }
+            })
+        });
+        Texture2D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            width,
+            height,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture resized to the given width and height using the given interpolation.
+    /// The resampling is done on premultiplied colors, so transparent texels do not bleed into the neighbouring texels,
+    /// while the pixel data format and the [Texture2D::premultiplied] flag of the result is the same as for this texture.
+    ///
+    pub fn resize(&self, width: u32, height: u32, interpolation: Interpolation) -> Texture2D {
+        let scale_x = self.width as f32 / width as f32;
+        let scale_y = self.height as f32 / height as f32;
+        let texels = (0..height).flat_map(|y| {
+            (0..width).map(move |x| {
+                let color = self.interpolate(
+                    (x as f32 + 0.5) * scale_x - 0.5,
+                    (y as f32 + 0.5) * scale_y - 0.5,
+                    interpolation,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                );
+                if self.premultiplied {
+                    color
+                } else {
+                    unpremultiply(color)
+                }
+            })
+        });
+        Texture2D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            width,
+            height,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture resized to the given width and height using the given interpolation, like [Texture2D::resize],
+    /// except that texture data which is assumed to be in sRGB color space (see [TextureDescriptor::is_srgb_candidate](crate::TextureDescriptor::is_srgb_candidate))
+    /// is converted to linear color space before resampling and back to sRGB color space afterwards.
+    /// This avoids that the result is too dark, for example a black and white texture is resized to a light gray instead of a middle gray.
+    ///
+    pub fn srgb_aware_resize(
+        &self,
+        width: u32,
+        height: u32,
+        interpolation: Interpolation,
+    ) -> Texture2D {
+        if !self.data.descriptor().is_srgb_candidate {
+            return self.resize(width, height, interpolation);
+        }
+        let linear = Texture2D {
+            data: TextureData::RgbaF32(
+                self.data
+                    .iter_rgba_f32()
+                    .map(|c| {
+                        [
+                            srgb_to_linear(c[0]),
+                            srgb_to_linear(c[1]),
+                            srgb_to_linear(c[2]),
+                            c[3],
+                        ]
+                    })
+                    .collect(),
+            ),
+            ..self.clone()
+        };
+        let resized = linear.resize(width, height, interpolation);
+        let texels = resized.data.iter_rgba_f32().map(|c| {
+            [
+                linear_to_srgb(c[0]),
+                linear_to_srgb(c[1]),
+                linear_to_srgb(c[2]),
+                c[3],
+            ]
+        });
+        Texture2D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            ..resized
+        }
+    }
+
+    ///
+    /// Copies the given source texture into this texture with the top left corner at the given pixel position.
+    /// The parts of the source texture that fall outside this texture are ignored.
+    /// The source texels are converted to the pixel data format of this texture and
+    /// premultiplied or unpremultiplied if the [Texture2D::premultiplied] flags of the two textures differ.
+    ///
+    pub fn blit(&mut self, source: &Texture2D, x: u32, y: u32) {
+        let width = source.width.min(self.width.saturating_sub(x));
+        let height = source.height.min(self.height.saturating_sub(y));
+        let mut texels: Vec<[f32; 4]> = self.data.iter_rgba_f32().collect();
+        for sy in 0..height {
+            for sx in 0..width {
+                let color = source.data.rgba_f32((sy * source.width + sx) as usize);
+                let color = match (source.premultiplied, self.premultiplied) {
+                    (false, true) => premultiply(color),
+                    (true, false) => unpremultiply(color),
+                    _ => color,
+                };
+                texels[((y + sy) * self.width + x + sx) as usize] = color;
+            }
+        }
+        self.data = self.data.same_format_from_rgba_f32(texels);
+    }
+
+    ///
+    /// Fills the rectangle with the top left corner at the given pixel position and the given width and height with the given non-premultiplied 8-bit RGBA color,
+    /// for example to draw debug overlays. The parts of the rectangle that fall outside this texture are ignored.
+    /// The color is converted to the pixel data format of this texture and premultiplied if the texture is [Texture2D::premultiplied].
+    ///
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: [u8; 4]) {
+        let (x0, y0) = (x.max(0) as i64, y.max(0) as i64);
+        let x1 = (x as i64 + width as i64).min(self.width as i64);
+        let y1 = (y as i64 + height as i64).min(self.height as i64);
+        self.set_texels((y0..y1).flat_map(|y| (x0..x1).map(move |x| (x, y))), color);
+    }
+
+    ///
+    /// Draws a one texel wide line between the two given pixel positions, both included, with the given non-premultiplied 8-bit RGBA color
+    /// using Bresenham's algorithm. The parts of the line that fall outside this texture are ignored.
+    /// The color is converted to the pixel data format of this texture and premultiplied if the texture is [Texture2D::premultiplied].
+    ///
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: [u8; 4]) {
+        let (mut x, mut y) = (x0 as i64, y0 as i64);
+        let (x1, y1) = (x1 as i64, y1 as i64);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (step_x, step_y) = ((x1 - x).signum(), (y1 - y).signum());
+        let mut error = dx + dy;
+        let mut positions = Vec::new();
+        loop {
+            positions.push((x, y));
+            if x == x1 && y == y1 {
+                break;
+            }
+            if 2 * error >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if 2 * error <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+        self.set_texels(positions.into_iter(), color);
+    }
+
+    ///
+    /// Sets the texels at the given pixel positions to the given non-premultiplied color and ignores the positions outside this texture.
+    ///
+    fn set_texels(&mut self, positions: impl Iterator<Item = (i64, i64)>, color: [u8; 4]) {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let indices = positions
+            .filter(|(x, y)| (0..width).contains(x) && (0..height).contains(y))
+            .map(|(x, y)| (y * width + x) as usize);
+        match &mut self.data {
+            TextureData::RgbaU8(data) if !self.premultiplied => {
+                for i in indices {
+                    data[i] = color;
+                }
+            }
+            _ => {
+                let color = color.map(|v| v as f32 / 255.0);
+                let color = if self.premultiplied {
+                    premultiply(color)
+                } else {
+                    color
+                };
+                let mut texels: Vec<[f32; 4]> = self.data.iter_rgba_f32().collect();
+                for i in indices {
+                    texels[i] = color;
+                }
+                self.data = self.data.same_format_from_rgba_f32(texels);
+            }
+        }
+    }
+
+    ///
+    /// Overwrites the pixels in the rectangle with the top left corner at the given pixel position with the pixels of the given region texture,
+    /// without reallocating the pixel data of this texture. In contrast to [Texture2D::blit], no conversion is done, so the region must have the same [TextureData] variant as this texture.
+    ///
+    /// Returns a [RegionOutOfBounds](crate::Error::RegionOutOfBounds) error if the region does not fit inside this texture
+    /// and a [TextureDataFormatMismatch](crate::Error::TextureDataFormatMismatch) error if the [TextureData] variants differ.
+    ///
+    pub fn write_region(&mut self, x: u32, y: u32, region: &Texture2D) -> crate::Result<()> {
+        if x as u64 + region.width as u64 > self.width as u64
+            || y as u64 + region.height as u64 > self.height as u64
+        {
+            return Err(crate::Error::RegionOutOfBounds(
+                x,
+                y,
+                region.width,
+                region.height,
+                self.width,
+                self.height,
+            ));
+        }
+        let width = self.width as usize;
+        let (x, y) = (x as usize, y as usize);
+        let region_width = region.width as usize;
+        macro_rules! copy_rows {
+            ($($variant:ident),*) => {
+                match (&mut self.data, &region.data) {
+                    $((TextureData::$variant(target), TextureData::$variant(source)) => {
+                        for (row, source_row) in source.chunks(region_width.max(1)).enumerate() {
+                            let start = (y + row) * width + x;
+                            target[start..start + source_row.len()].copy_from_slice(source_row);
+                        }
+                    })*
+                    _ => return Err(crate::Error::TextureDataFormatMismatch),
+                }
+            };
+        }
+        copy_rows!(
+            RU8, RgU8, RgbU8, RgbaU8, RU16, RgU16, RgbU16, RgbaU16, RF16, RgF16, RgbF16, RgbaF16,
+            RF32, RgF32, RgbF32, RgbaF32
+        );
+        Ok(())
+    }
+
+    ///
+    /// Returns the absolute per-channel difference between this texture and the other texture, for example to compare rendered images.
+    /// The difference is computed on normalized colors, including the alpha channel, and the result has the same [TextureData] format as this texture.
+    ///
+    /// Returns a [TextureSizeMismatch](crate::Error::TextureSizeMismatch) error if the textures do not have the same size.
+    ///
+    pub fn difference(&self, other: &Texture2D) -> crate::Result<Texture2D> {
+        self.check_same_size(other)?;
+        let texels = self
+            .data
+            .iter_rgba_f32()
+            .zip(other.data.iter_rgba_f32())
+            .map(|(a, b)| [0, 1, 2, 3].map(|i| (a[i] - b[i]).abs()));
+        Ok(Texture2D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            ..self.clone()
+        })
+    }
+
+    ///
+    /// Returns the root-mean-square error between this texture and the other texture, for example to score rendered images against reference images.
+    /// The error is computed on normalized values over the channels of the [TextureData] format of this texture,
+    /// where the other texture is converted in the same way as in [Texture2D::to_f32_buffer].
+    ///
+    /// Returns a [TextureSizeMismatch](crate::Error::TextureSizeMismatch) error if the textures do not have the same size.
+    ///
+    pub fn rmse(&self, other: &Texture2D) -> crate::Result<f32> {
+        self.check_same_size(other)?;
+        let channels = self.data.descriptor().channels as usize;
+        let a = self.to_f32_buffer(channels);
+        let b = other.to_f32_buffer(channels);
+        if a.is_empty() {
+            return Ok(0.0);
+        }
+        let sum: f64 = a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| (*a as f64 - *b as f64).powi(2))
+            .sum();
+        Ok((sum / a.len() as f64).sqrt() as f32)
+    }
+
+    ///
+    /// Returns the peak signal-to-noise ratio in decibels between this texture and the other texture based on the [Texture2D::rmse] with a peak value of `1.0`.
+    /// Higher is more similar and identical textures result in infinity.
+    ///
+    /// Returns a [TextureSizeMismatch](crate::Error::TextureSizeMismatch) error if the textures do not have the same size.
+    ///
+    pub fn psnr(&self, other: &Texture2D) -> crate::Result<f32> {
+        Ok(-20.0 * self.rmse(other)?.log10())
+    }
+
+    ///
+    /// Linearly interpolates between this texture and the other texture, for example to cross-fade between two textures.
+    /// The interpolation factor `t` is clamped to the `[0..1]` range, where `0` returns this texture and `1` the other texture.
+    /// The interpolation is done on normalized colors, including the alpha channel, and the result has the same [TextureData] format
+    /// and [Texture2D::premultiplied] flag as this texture.
+    ///
+    /// Returns a [TextureSizeMismatch](crate::Error::TextureSizeMismatch) error if the textures do not have the same size.
+    ///
+    pub fn mix(&self, other: &Texture2D, t: f32) -> crate::Result<Texture2D> {
+        self.check_same_size(other)?;
+        let t = t.clamp(0.0, 1.0);
+        let texels = self
+            .data
+            .iter_rgba_f32()
+            .zip(other.data.iter_rgba_f32())
+            .map(|(a, b)| {
+                let b = match (other.premultiplied, self.premultiplied) {
+                    (false, true) => premultiply(b),
+                    (true, false) => unpremultiply(b),
+                    _ => b,
+                };
+                [0, 1, 2, 3].map(|i| a[i] * (1.0 - t) + b[i] * t)
+            });
+        Ok(Texture2D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            ..self.clone()
+        })
+    }
+
+    ///
+    /// Composites this texture over the given background texture using the standard source-over operator.
+    /// The result has the same [TextureData] format and [Texture2D::premultiplied] flag as the background.
+    ///
+    /// Returns a [TextureSizeMismatch](crate::Error::TextureSizeMismatch) error if the textures do not have the same size.
+    ///
+    pub fn blend_over(&self, background: &Texture2D) -> crate::Result<Texture2D> {
+        background.check_same_size(self)?;
+        let texels = (0..self.data.len()).map(|i| {
+            let source = self.premultiplied_texel_at(i);
+            let destination = background.premultiplied_texel_at(i);
+            let color = [0, 1, 2, 3].map(|c| source[c] + destination[c] * (1.0 - source[3]));
+            if background.premultiplied {
+                color
+            } else {
+                unpremultiply(color)
+            }
+        });
+        Ok(Texture2D {
+            data: background.data.same_format_from_rgba_f32(texels),
+            ..background.clone()
+        })
+    }
+
+    ///
+    /// Composites the given layers, ordered from bottom to top, using the standard source-over operator in a single pass,
+    /// which gives the same result as repeatedly calling [Texture2D::blend_over] but without the intermediate textures and rounding.
+    /// The result has the same [TextureData] format, premultiplied flag and sampling settings as the bottom layer.
+    ///
+    /// If no layers are given, an empty texture with [TextureData::RgbaU8] data is returned.
+    ///
+    /// Returns a [TextureSizeMismatch](crate::Error::TextureSizeMismatch) error if the layers do not have the same size.
+    ///
+    pub fn composite(layers: &[&Texture2D]) -> crate::Result<Texture2D> {
+        let Some(bottom) = layers.first() else {
+            return Ok(Texture2D {
+                data: TextureData::RgbaU8(Vec::new()),
+                width: 0,
+                height: 0,
+                ..Default::default()
+            });
+        };
+        for layer in &layers[1..] {
+            bottom.check_same_size(layer)?;
+        }
+        let texels = (0..bottom.data.len()).map(|i| {
+            let mut color = bottom.premultiplied_texel_at(i);
+            for layer in &layers[1..] {
+                let source = layer.premultiplied_texel_at(i);
+                color = [0, 1, 2, 3].map(|c| source[c] + color[c] * (1.0 - source[3]));
+            }
+            if bottom.premultiplied {
+                color
+            } else {
+                unpremultiply(color)
+            }
+        });
+        Ok(Texture2D {
+            data: bottom.data.same_format_from_rgba_f32(texels),
+            ..(*bottom).clone()
+        })
+    }
+
+    ///
+    /// Composites this texture over the given 8-bit RGBA background color using the standard source-over operator and
+    /// returns the result as [TextureData::RgbU8] data, for example before saving to a format without an alpha channel like JPEG.
+    ///
+    pub fn flatten_onto(&self, background: [u8; 4]) -> Texture2D {
+        let destination = premultiply(background.map(|v| v as f32 / 255.0));
+        let texels = (0..self.data.len()).map(|i| {
+            let source = self.premultiplied_texel_at(i);
+            unpremultiply([0, 1, 2, 3].map(|c| source[c] + destination[c] * (1.0 - source[3])))
+        });
+        Texture2D {
+            data: TextureData::RgbU8(Vec::new()).same_format_from_rgba_f32(texels),
+            premultiplied: false,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Blends a black and white checkerboard pattern with square cells of the given size in texels over this texture, for example to debug UV seams.
+    /// The opacity in the `[0..1]` range determines how much of the pattern is visible, where `1.0` replaces the color completely.
+    /// The cell in the top left corner is white, the alpha channel is left unchanged and the [TextureData] format is preserved.
+    ///
+    pub fn overlay_checkerboard(&mut self, cell: u32, opacity: f32) {
+        let cell = cell.max(1);
+        let opacity = opacity.clamp(0.0, 1.0);
+        let width = self.width;
+        let premultiplied = self.premultiplied;
+        let texels = self.data.iter_rgba_f32().enumerate().map(|(i, c)| {
+            let (x, y) = (i as u32 % width / cell, i as u32 / width / cell);
+            let checker = if (x + y) & 1 == 0 { 1.0 } else { 0.0 };
+            let checker = if premultiplied {
+                checker * c[3]
+            } else {
+                checker
+            };
+            let blend = |v: f32| v * (1.0 - opacity) + checker * opacity;
+            [blend(c[0]), blend(c[1]), blend(c[2]), c[3]]
+        });
+        self.data = self.data.same_format_from_rgba_f32(texels);
+    }
+
+    ///
+    /// Returns a [TextureSizeMismatch](crate::Error::TextureSizeMismatch) error if the other texture does not have the same size as this texture.
+    ///
+    fn check_same_size(&self, other: &Texture2D) -> crate::Result<()> {
+        if self.dimensions() != other.dimensions() {
+            Err(crate::Error::TextureSizeMismatch(
+                other.width,
+                other.height,
+                self.width,
+                self.height,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture which is extended by the given number of texels on each side.
+    /// The new texels are filled according to [Texture2D::wrap_s] and [Texture2D::wrap_t], which for example is useful before applying a convolution filter.
+    /// If this texture is empty, the new texels are transparent black.
+    ///
+    pub fn extend_edges(&self, border: u32) -> Texture2D {
+        let width = self.width + 2 * border;
+        let height = self.height + 2 * border;
+        if self.width == 0 || self.height == 0 {
+            return Texture2D {
+                data: self
+                    .data
+                    .same_format_from_rgba_f32(vec![[0.0; 4]; (width * height) as usize]),
+                width,
+                height,
+                ..self.clone()
+            };
+        }
+        let texels = (0..height as i64).flat_map(|y| {
+            let y = wrap(y - border as i64, self.height, self.wrap_t);
+            (0..width as i64).map(move |x| {
+                let x = wrap(x - border as i64, self.width, self.wrap_s);
+                self.data.rgba_f32((y * self.width + x) as usize)
+            })
+        });
+        Texture2D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            width,
+            height,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a binary mask with [TextureData::RU8] data, for example for decals and cutouts, which is 255 where the normalized value
+    /// of the given channel is greater than the threshold and 0 elsewhere, or the opposite if `invert` is true.
+    /// The channel index refers to the red, green, blue and alpha channels in the same way as [TextureData::iter_rgba_f32],
+    /// so one channel data is used for red, green and blue and a missing alpha channel is `1.0`.
+    ///
+    /// Returns an [InvalidChannelIndex](crate::Error::InvalidChannelIndex) error if the channel index is not less than 4.
+    ///
+    pub fn threshold(
+        &self,
+        channel: usize,
+        threshold: f32,
+        invert: bool,
+    ) -> crate::Result<Texture2D> {
+        if channel >= 4 {
+            return Err(crate::Error::InvalidChannelIndex(channel, 4));
+        }
+        Ok(Texture2D {
+            data: TextureData::RU8(
+                self.data
+                    .iter_rgba_f32()
+                    .map(|c| {
+                        if (c[channel] > threshold) != invert {
+                            255
+                        } else {
+                            0
+                        }
+                    })
+                    .collect(),
+            ),
+            ..self.clone()
+        })
+    }
+
+    ///
+    /// Returns a single channel texture containing the channel with the given index of this texture,
+    /// for example to use the green channel of a packed texture as a mask.
+    /// The result has the one channel [TextureData] variant with the same precision as this texture, for example [TextureData::RU8] for [TextureData::RgbaU8] data.
+    ///
+    /// Returns an [InvalidChannelIndex](crate::Error::InvalidChannelIndex) error if the index is not less than the number of channels.
+    ///
+    pub fn channel(&self, index: usize) -> crate::Result<Texture2D> {
+        let channels = self.data.descriptor().channels;
+        if index >= channels as usize {
+            return Err(crate::Error::InvalidChannelIndex(index, channels));
+        }
+        let data = match &self.data {
+            TextureData::RU8(values) => TextureData::RU8(values.clone()),
+            TextureData::RgU8(values) => {
+                TextureData::RU8(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RgbU8(values) => {
+                TextureData::RU8(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RgbaU8(values) => {
+                TextureData::RU8(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RU16(values) => TextureData::RU16(values.clone()),
+            TextureData::RgU16(values) => {
+                TextureData::RU16(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RgbU16(values) => {
+                TextureData::RU16(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RgbaU16(values) => {
+                TextureData::RU16(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RF16(values) => TextureData::RF16(values.clone()),
+            TextureData::RgF16(values) => {
+                TextureData::RF16(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RgbF16(values) => {
+                TextureData::RF16(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RgbaF16(values) => {
+                TextureData::RF16(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RF32(values) => TextureData::RF32(values.clone()),
+            TextureData::RgF32(values) => {
+                TextureData::RF32(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RgbF32(values) => {
+                TextureData::RF32(values.iter().map(|c| c[index]).collect())
+            }
+            TextureData::RgbaF32(values) => {
+                TextureData::RF32(values.iter().map(|c| c[index]).collect())
+            }
+        };
+        Ok(Texture2D {
+            data,
+            premultiplied: false,
+            ..self.clone()
+        })
+    }
+
+    ///
+    /// Packs the given occlusion, roughness and metallic textures into the red, green and blue channels of a single [TextureData::RgbU8] texture,
+    /// which is the layout expected by for example glTF, where occlusion is stored in the red channel and roughness and metallic in the green and blue channels.
+    /// The first channel of each input is used, which is the luminance for RGB data (see [Texture2D::to_f32_buffer]).
+    /// The sampling settings of the result are the ones of the occlusion texture.
+    ///
+    /// Returns a [TextureSizeMismatch](crate::Error::TextureSizeMismatch) error if the textures do not have the same size.
+    ///
+    pub fn pack_orm(
+        occlusion: &Texture2D,
+        roughness: &Texture2D,
+        metallic: &Texture2D,
+    ) -> crate::Result<Texture2D> {
+        occlusion.check_same_size(roughness)?;
+        occlusion.check_same_size(metallic)?;
+        let u = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let occlusion_values = occlusion.to_f32_buffer(1);
+        let roughness_values = roughness.to_f32_buffer(1);
+        let metallic_values = metallic.to_f32_buffer(1);
+        Ok(Texture2D {
+            name: String::new(),
+            data: TextureData::RgbU8(
+                (0..occlusion_values.len())
+                    .map(|i| {
+                        [
+                            u(occlusion_values[i]),
+                            u(roughness_values[i]),
+                            u(metallic_values[i]),
+                        ]
+                    })
+                    .collect(),
+            ),
+            premultiplied: false,
+            ..occlusion.clone()
+        })
+    }
+
+    ///
+    /// Returns the `(x, y, width, height)` pixel rectangle which tightly contains all texels with an 8-bit alpha value above the given threshold,
+    /// for example to find the visible content of a sprite before packing it.
+    ///
+    /// Returns `None` if no texel is above the threshold or if the texture has no alpha channel.
+    ///
+    pub fn content_bounds(&self, alpha_threshold: u8) -> Option<(u32, u32, u32, u32)> {
+        if !self.data.has_alpha() {
+            return None;
+        }
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (i, color) in self.rgba_u8_texels().enumerate() {
+            if color[3] > alpha_threshold {
+                let x = i as u32 % self.width;
+                let y = i as u32 / self.width;
+                bounds = Some(match bounds {
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                    None => (x, y, x, y),
+                });
+            }
+        }
+        bounds.map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+    }
+
+    ///
+    /// Returns a copy of this texture which is flipped or rotated according to the given orientation, for example to match the
+    /// cube map face conventions of a specific graphics API. The width and height are swapped for 90 and 270 degree rotations.
+    /// The texels are moved without any conversion, so the result has the same [TextureData] variant as this texture.
+    ///
+    pub fn reorient(&self, orientation: TextureOrientation) -> Texture2D {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let (width, height) = match orientation {
+            TextureOrientation::Rotate90 | TextureOrientation::Rotate270 => (h, w),
+            _ => (w, h),
+        };
+        let source_index = |x: usize, y: usize| match orientation {
+            TextureOrientation::Identity => y * w + x,
+            TextureOrientation::FlipHorizontal => y * w + w - 1 - x,
+            TextureOrientation::FlipVertical => (h - 1 - y) * w + x,
+            TextureOrientation::Rotate90 => (h - 1 - x) * w + y,
+            TextureOrientation::Rotate180 => (h - 1 - y) * w + w - 1 - x,
+            TextureOrientation::Rotate270 => x * w + w - 1 - y,
+        };
+        let indices: Vec<usize> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| source_index(x, y))
+            .collect();
+        Texture2D {
+            data: self.select_texels(&indices),
+            width: width as u32,
+            height: height as u32,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of the rectangle of this texture with the top left corner at the given pixel position and the given size.
+    /// The rectangle is clamped to the bounds of this texture and the texels are copied without any conversion.
+    ///
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Texture2D {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+        let indices: Vec<usize> = (y..y + height)
+            .flat_map(|y| (x..x + width).map(move |x| (y * self.width + x) as usize))
+            .collect();
+        Texture2D {
+            data: self.select_texels(&indices),
+            width,
+            height,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture cropped to the largest width and height that are multiples of the given block size,
+    /// for example to satisfy the alignment requirement of block compression formats like BC and ASTC.
+    /// The texels in the right and bottom edges are removed.
+    ///
+    pub fn crop_to_multiple_of(&self, block: u32) -> Texture2D {
+        let block = block.max(1);
+        self.crop(
+            0,
+            0,
+            self.width / block * block,
+            self.height / block * block,
+        )
+    }
+
+    ///
+    /// Returns a copy of this texture padded to the smallest width and height that are multiples of the given block size,
+    /// for example to satisfy the alignment requirement of block compression formats like BC and ASTC.
+    /// The new texels are added at the right and bottom edges and filled with the given non-premultiplied 8-bit RGBA color,
+    /// which is converted to the pixel data format of this texture and premultiplied if the texture is [Texture2D::premultiplied].
+    ///
+    pub fn pad_to_multiple_of(&self, block: u32, fill: [u8; 4]) -> Texture2D {
+        let block = block.max(1);
+        let width = self.width.div_ceil(block) * block;
+        let height = self.height.div_ceil(block) * block;
+        let mut padded = Texture2D {
+            data: self.data.same_format_from_rgba_f32(std::iter::repeat_n(
+                [0.0; 4],
+                width as usize * height as usize,
+            )),
+            width,
+            height,
+            ..self.clone()
+        };
+        padded.fill_rect(0, 0, width, height, fill);
+        padded.blit(self, 0, 0);
+        padded
+    }
+
+    ///
+    /// Returns a copy of this texture cropped to the [Texture2D::content_bounds] with the given alpha threshold,
+    /// or `None` if no texel is above the threshold or the texture has no alpha channel.
+    ///
+    pub fn trim_transparent(&self, alpha_threshold: u8) -> Option<Texture2D> {
+        self.content_bounds(alpha_threshold)
+            .map(|(x, y, width, height)| self.crop(x, y, width, height))
+    }
+
+    ///
+    /// Returns the texels at the given indices in the same [TextureData] variant as this texture.
+    ///
+    fn select_texels(&self, indices: &[usize]) -> TextureData {
+        macro_rules! select {
+            ($($variant:ident),*) => {
+                match &self.data {
+                    $(TextureData::$variant(values) => {
+                        TextureData::$variant(indices.iter().map(|i| values[*i]).collect())
+                    })*
+                }
+            };
+        }
+        select!(
+            RU8, RgU8, RgbU8, RgbaU8, RU16, RgU16, RgbU16, RgbaU16, RF16, RgF16, RgbF16, RgbaF16,
+            RF32, RgF32, RgbF32, RgbaF32
+        )
+    }
+
+    ///
+    /// Returns whether or not the width and height of this texture are both a power of two,
+    /// which some GPUs and graphics APIs require for mipmapping and repeat wrapping.
+    ///
+    pub fn is_power_of_two(&self) -> bool {
+        self.width.is_power_of_two() && self.height.is_power_of_two()
+    }
+
+    ///
+    /// Returns the smallest power of two width and height that are at least the width and height of this texture,
+    /// for example the size to [resize](Texture2D::resize) to or to [blit](Texture2D::blit) into before uploading the texture to a GPU that requires power of two textures.
+    ///
+    pub fn next_power_of_two_dimensions(&self) -> (u32, u32) {
+        (
+            self.width.next_power_of_two(),
+            self.height.next_power_of_two(),
+        )
+    }
+
+    ///
+    /// Returns whether or not mipmaps can be created for this texture, which is the case if the width and height are both a power of two.
+    ///
+    pub fn mipmaps_supported(&self) -> bool {
+        self.is_power_of_two()
+    }
+
+    ///
+    /// Generates the mipmap levels below the base level, ie. the textures with half the width and height of the previous level down to a 1x1 texture.
+    /// Each level is downsampled from the previous level with the given [DownsampleKernel] on premultiplied colors, so transparent texels do not darken the colors of the neighbouring texels.
+    /// Kernels wider than a 2x2 block sample outside the texture according to [Texture2D::wrap_s] and [Texture2D::wrap_t].
+    /// The whole chain is computed with floating point precision and each level is converted to the pixel data format of this texture only once, so rounding errors do not accumulate.
+    ///
+    /// Returns a [MipmapUnsupported](crate::Error::MipmapUnsupported) error if mipmaps are not [supported](Texture2D::mipmaps_supported) for this texture.
+    ///
+    pub fn generate_mipmaps(&self, kernel: DownsampleKernel) -> crate::Result<Vec<Texture2D>> {
+        if !self.mipmaps_supported() {
+            return Err(crate::Error::MipmapUnsupported {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        let mut levels: Vec<Texture2D> = Vec::new();
+        let mut width = self.width as usize;
+        let mut height = self.height as usize;
+        let mut texels: Vec<[f32; 4]> = self
+            .data
+            .iter_rgba_f32()
+            .map(|c| {
+                if self.premultiplied {
+                    c
+                } else {
+                    premultiply(c)
+                }
+            })
+            .collect();
+        while width > 1 || height > 1 {
+            let (level_width, level_height) = ((width / 2).max(1), (height / 2).max(1));
+            let weights_x = downsample_weights(kernel, width, level_width, self.wrap_s);
+            let weights_y = downsample_weights(kernel, height, level_height, self.wrap_t);
+            let filter = |taps: &[(usize, f32)], fetch: &dyn Fn(usize) -> [f32; 4]| {
+                let mut texel = [0.0; 4];
+                for (index, weight) in taps {
+                    let source = fetch(*index);
+                    for c in 0..4 {
+                        texel[c] += source[c] * weight;
+                    }
+                }
+                texel
+            };
+            let horizontal: Vec<[f32; 4]> = (0..height)
+                .flat_map(|y| {
+                    let texels = &texels;
+                    weights_x
+                        .iter()
+                        .map(move |taps| filter(taps, &|x| texels[y * width + x]))
+                })
+                .collect();
+            texels = weights_y
+                .iter()
+                .flat_map(|taps| {
+                    let horizontal = &horizontal;
+                    (0..level_width)
+                        .map(move |x| filter(taps, &|y| horizontal[y * level_width + x]))
+                })
+                .collect();
+            width = level_width;
+            height = level_height;
+            levels.push(Texture2D {
+                data: self.data.same_format_from_rgba_f32(texels.iter().map(|c| {
+                    // Kernels with negative lobes can produce a negative alpha next to sharp edges.
+                    let c = [c[0], c[1], c[2], c[3].max(0.0)];
+                    if self.premultiplied {
+                        c
+                    } else {
+                        unpremultiply(c)
+                    }
+                })),
+                width: width as u32,
+                height: height as u32,
+                ..self.clone()
+            });
+        }
+        Ok(levels)
+    }
+
+    ///
+    /// Returns a copy of this texture which tiles without visible seams, for example to use a photo as a repeating material texture.
+    /// The texture is offset by half its width and height with wrapping, which moves the seams to the center of the texture,
+    /// and the seams are then cross-faded with the original texture over the given number of texels on each side of the seams.
+    /// The blending is done on premultiplied colors and the result has the same [TextureData] format as this texture.
+    ///
+    pub fn make_tileable(&self, blend: u32) -> Texture2D {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let weight = |coord: usize, size: usize| {
+            if blend == 0 {
+                return 0.0;
+            }
+            let distance = (coord as f32 + 0.5 - (size / 2) as f32).abs();
+            (1.0 - distance / blend as f32).max(0.0)
+        };
+        // The horizontal and vertical seams are handled in separate passes,
+        // otherwise blending one seam would bring back the mismatched edges along the other.
+        let mut texels: Vec<[f32; 4]> = (0..width * height)
+            .map(|i| self.premultiplied_texel_at(i))
+            .collect();
+        for horizontal in [true, false] {
+            texels = (0..width * height)
+                .map(|i| {
+                    let (x, y) = (i % width, i / width);
+                    let (offset, t) = if horizontal {
+                        (y * width + (x + width / 2) % width, weight(x, width))
+                    } else {
+                        (((y + height / 2) % height) * width + x, weight(y, height))
+                    };
+                    let (a, b) = (texels[offset], texels[i]);
+                    [0, 1, 2, 3].map(|c| a[c] * (1.0 - t) + b[c] * t)
+                })
+                .collect();
+        }
+        let texels = texels.into_iter().map(|color| {
+            if self.premultiplied {
+                color
+            } else {
+                unpremultiply(color)
+            }
+        });
+        Texture2D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this texture blurred with a box filter which averages all texels within the given radius in both directions.
+    /// The blur is separable and is done on premultiplied colors, the edges are handled according to [Texture2D::wrap_s] and [Texture2D::wrap_t].
+    ///
+    pub fn box_blur(&self, radius: u32) -> Texture2D {
+        let size = 2 * radius as usize + 1;
+        self.convolve_separable(&vec![1.0 / size as f32; size])
+    }
+
+    ///
+    /// Returns a copy of this texture blurred with a Gaussian filter with the given standard deviation in pixels.
+    /// The blur is separable and is done on premultiplied colors, the edges are handled according to [Texture2D::wrap_s] and [Texture2D::wrap_t].
+    ///
+    pub fn gaussian_blur(&self, sigma: f32) -> Texture2D {
+        if sigma <= 0.0 {
+            return self.clone();
+        }
+        let radius = (3.0 * sigma).ceil() as i32;
+        let kernel: Vec<f32> = (-radius..=radius)
+            .map(|x| (-((x * x) as f32) / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let sum: f32 = kernel.iter().sum();
+        self.convolve_separable(&kernel.iter().map(|w| w / sum).collect::<Vec<_>>())
+    }
+
+    ///
+    /// Convolves the premultiplied colors with the given kernel, first horizontally and then vertically.
+    /// The kernel must have an odd length and its center is at the middle element.
+    ///
+    fn convolve_separable(&self, kernel: &[f32]) -> Texture2D {
+        let radius = (kernel.len() / 2) as i64;
+        let (width, height) = (self.width as i64, self.height as i64);
+        let convolve = |fetch: &dyn Fn(i64) -> [f32; 4]| {
+            let mut color = [0.0; 4];
+            for (i, weight) in kernel.iter().enumerate() {
+                let c = fetch(i as i64 - radius);
+                for j in 0..4 {
+                    color[j] += weight * c[j];
+                }
+            }
+            color
+        };
+        let horizontal: Vec<[f32; 4]> = (0..height)
+            .flat_map(|y| {
+                (0..width).map(move |x| {
+                    convolve(&|offset| {
+                        self.premultiplied_texel(x + offset, y, self.wrap_s, Wrapping::ClampToEdge)
+                    })
+                })
+            })
+            .collect();
+        let texels = (0..height).flat_map(|y| {
+            let horizontal = &horizontal;
+            (0..width).map(move |x| {
+                let color = convolve(&|offset| {
+                    let y = wrap(y + offset, self.height, self.wrap_t) as i64;
+                    horizontal[(y * width + x) as usize]
+                });
+                if self.premultiplied {
+                    color
+                } else {
+                    unpremultiply(color)
+                }
+            })
+        });
+        Texture2D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a signed distance field of this texture with [TextureData::RU8] data, for example to render crisp scalable masks and text.
+    /// A texel is inside the shape if its alpha value is at least `0.5` or, if the texture has no alpha channel, if its luminance is at least `0.5`.
+    /// The distance in texels from the center of each texel to the edge of the shape is mapped to the `[0..255]` range such that the edge is at the midpoint,
+    /// texels inside the shape at a distance of `spread` or more are 255 and texels outside the shape at a distance of `spread` or more are 0.
+    ///
+    pub fn to_sdf(&self, spread: f32) -> Texture2D {
+        let inside: Vec<bool> = if self.data.has_alpha() {
+            self.data.iter_rgba_f32().map(|c| c[3] >= 0.5).collect()
+        } else {
+            self.data
+                .iter_rgba_f32()
+                .map(|c| luminance(c) >= 0.5)
+                .collect()
+        };
+        let distance_to_outside = self.distance_transform(&inside, false);
+        let distance_to_inside = self.distance_transform(&inside, true);
+        let spread = spread.max(f32::EPSILON);
+        Texture2D {
+            data: TextureData::RU8(
+                (0..inside.len())
+                    .map(|i| {
+                        let distance = if inside[i] {
+                            distance_to_outside[i] - 0.5
+                        } else {
+                            0.5 - distance_to_inside[i]
+                        };
+                        ((0.5 + 0.5 * distance / spread).clamp(0.0, 1.0) * 255.0).round() as u8
+                    })
+                    .collect(),
+            ),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns the euclidean distance from each texel to the closest texel where the mask has the given value,
+    /// computed exactly with two passes of the one-dimensional transform by Felzenszwalb and Huttenlocher.
+    ///
+    fn distance_transform(&self, mask: &[bool], value: bool) -> Vec<f32> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut distances: Vec<f32> = mask
+            .iter()
+            .map(|m| if *m == value { 0.0 } else { f32::INFINITY })
+            .collect();
+        let mut column = vec![0.0; height];
+        for x in 0..width {
+            for y in 0..height {
+                column[y] = distances[y * width + x];
+            }
+            let column = distance_transform_1d(&column);
+            for y in 0..height {
+                distances[y * width + x] = column[y];
+            }
+        }
+        for row in distances.chunks_mut(width.max(1)) {
+            let transformed = distance_transform_1d(row);
+            row.copy_from_slice(&transformed);
+        }
+        distances.iter().map(|d| d.sqrt()).collect()
+    }
+
+    ///
+    /// Folds the given function over all texels as normalized RGBA values, in the same order and with the same conversion as [TextureData::iter_rgba_f32],
+    /// starting with the given initial value. This makes it possible to compute custom statistics, like the coverage or the brightest texel,
+    /// without matching on the [TextureData] variant. The colors of a [Texture2D::premultiplied] texture are passed as they are stored, ie. premultiplied.
+    ///
+    pub fn reduce<T, F: Fn(T, [f32; 4]) -> T>(&self, init: T, f: F) -> T {
+        self.data.iter_rgba_f32().fold(init, f)
+    }
+
+    ///
+    /// Returns the average color of this texture as non-premultiplied RGBA values in the `[0..1]` range for 8- and 16-bit data.
+    /// If `alpha_weighted` is true, the color of each texel is weighted by its alpha value, so transparent texels do not affect the average color,
+    /// otherwise all texels count the same. The alpha value is always the mean of the alpha values of all texels.
+    ///
+    pub fn average_color(&self, alpha_weighted: bool) -> [f32; 4] {
+        let (sum, weight) = self.reduce(([0.0f64; 4], 0.0f64), |(mut sum, weight), c| {
+            let c = if self.premultiplied {
+                unpremultiply(c)
+            } else {
+                c
+            };
+            let w = if alpha_weighted { c[3] as f64 } else { 1.0 };
+            for i in 0..3 {
+                sum[i] += c[i] as f64 * w;
+            }
+            sum[3] += c[3] as f64;
+            (sum, weight + w)
+        });
+        let count = self.data.len().max(1) as f64;
+        let weight = if weight > 0.0 { weight } else { 1.0 };
+        [
+            (sum[0] / weight) as f32,
+            (sum[1] / weight) as f32,
+            (sum[2] / weight) as f32,
+            (sum[3] / count) as f32,
+        ]
+    }
+
+    ///
+    /// Removes the alpha channel of this texture if every texel is fully opaque, which saves memory and bandwidth without changing the appearance,
+    /// for example for PNG files that are saved as RGBA but do not use the alpha channel.
+    /// RGBA data is converted to RGB data and, in line with [TextureData::has_alpha], RG data to R data with the same precision.
+    /// Returns whether the alpha channel was removed.
+    ///
+    pub fn strip_redundant_alpha(&mut self) -> bool {
+        macro_rules! strip {
+            ($($from:ident => $to:ident, $opaque:expr, |$v:ident| $texel:expr);*) => {
+                match &self.data {
+                    $(TextureData::$from(values) if values.iter().all(|v| v[v.len() - 1] == $opaque) => {
+                        TextureData::$to(values.iter().map(|$v| $texel).collect())
+                    })*
+                    _ => return false,
+                }
+            };
+        }
+        self.data = strip!(
+            RgU8 => RU8, u8::MAX, |v| v[0];
+            RgbaU8 => RgbU8, u8::MAX, |v| [v[0], v[1], v[2]];
+            RgU16 => RU16, u16::MAX, |v| v[0];
+            RgbaU16 => RgbU16, u16::MAX, |v| [v[0], v[1], v[2]];
+            RgF16 => RF16, crate::f16::ONE, |v| v[0];
+            RgbaF16 => RgbF16, crate::f16::ONE, |v| [v[0], v[1], v[2]];
+            RgF32 => RF32, 1.0, |v| v[0];
+            RgbaF32 => RgbF32, 1.0, |v| [v[0], v[1], v[2]]
+        );
+        self.premultiplied = false;
+        true
+    }
+
+    ///
+    /// Returns the number of distinct colors in this texture.
+    /// The colors are compared as 8-bit RGBA values, so data with a higher precision is quantized to 8 bits first.
+    ///
+    pub fn distinct_colors(&self) -> usize {
+        self.rgba_u8_texels()
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    }
+
+    ///
+    /// Returns a palette of at most `max` 8-bit RGBA colors that represents the colors in this texture, found using median cut quantization.
+    /// If the texture contains `max` or fewer [distinct colors](Texture2D::distinct_colors), the palette contains exactly those colors.
+    /// The palette is sorted and empty if this texture has no texels.
+    ///
+    pub fn extract_palette(&self, max: usize) -> Vec<[u8; 4]> {
+        if max == 0 || self.width == 0 || self.height == 0 {
+            return Vec::new();
+        }
+        let mut counts = std::collections::HashMap::new();
+        for color in self.rgba_u8_texels() {
+            *counts.entry(color).or_insert(0usize) += 1;
+        }
+        let mut colors: Vec<([u8; 4], usize)> = counts.into_iter().collect();
+        colors.sort();
+        let mut boxes = vec![colors];
+        while boxes.len() < max {
+            // Split the box with the largest range in any channel at the median of that channel.
+            let widest = boxes
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| b.len() > 1)
+                .map(|(i, b)| {
+                    let (channel, range) = (0..4)
+                        .map(|channel| {
+                            let min = b.iter().map(|(c, _)| c[channel]).min().unwrap();
+                            let max = b.iter().map(|(c, _)| c[channel]).max().unwrap();
+                            (channel, max - min)
+                        })
+                        .max_by_key(|(_, range)| *range)
+                        .unwrap();
+                    (i, channel, range)
+                })
+                .max_by_key(|(_, _, range)| *range);
+            let (i, channel) = match widest {
+                Some((i, channel, _)) => (i, channel),
+                None => break,
+            };
+            let mut b = boxes.remove(i);
+            b.sort_by_key(|(c, _)| c[channel]);
+            let total: usize = b.iter().map(|(_, count)| count).sum();
+            let mut accumulated = 0;
+            let mut split = 1;
+            for (j, (_, count)) in b.iter().enumerate() {
+                accumulated += count;
+                if 2 * accumulated >= total {
+                    split = (j + 1).clamp(1, b.len() - 1);
+                    break;
+                }
+            }
+            let rest = b.split_off(split);
+            boxes.push(b);
+            boxes.push(rest);
+        }
+        let mut palette: Vec<[u8; 4]> = boxes
+            .iter()
+            .map(|b| {
+                let total: usize = b.iter().map(|(_, count)| count).sum();
+                let mut color = [0; 4];
+                for channel in 0..4 {
+                    let sum: usize = b.iter().map(|(c, count)| c[channel] as usize * count).sum();
+                    color[channel] = ((sum + total / 2) / total) as u8;
+                }
+                color
+            })
+            .collect();
+        palette.sort();
+        palette
+    }
+
+    ///
+    /// Constructs a texture with [TextureData::RU16] data from a 16-bit depth buffer, for example read back after a depth prepass,
+    /// where the depth values are given row by row starting with the top row.
+    /// The texture uses nearest interpolation and no mipmaps, since interpolated depth values are rarely meaningful.
+    /// Use [Texture2D::visualize_depth] to turn the depth values into a viewable grayscale image.
+    ///
+    /// Returns an [InvalidBufferLength](crate::Error::InvalidBufferLength) error if the length of the depth buffer is not `width * height`.
+    ///
+    pub fn from_depth16(width: u32, height: u32, depth: &[u16]) -> crate::Result<Texture2D> {
+        let expected = width as usize * height as usize;
+        if depth.len() != expected {
+            return Err(crate::Error::InvalidBufferLength(
+                "depth".to_owned(),
+                expected,
+                depth.len(),
+            ));
+        }
+        Ok(Texture2D {
+            name: "depth".to_owned(),
+            data: TextureData::RU16(depth.to_vec()),
+            width,
+            height,
+            min_filter: Interpolation::Nearest,
+            mag_filter: Interpolation::Nearest,
+            mip_map_filter: None,
+            ..Default::default()
+        })
+    }
+
+    ///
+    /// Returns a grayscale copy of this depth texture with [TextureData::RU8] data, where the near plane is black and the far plane is white.
+    /// The first channel of this texture is interpreted as a normalized depth value in the `[0..1]` range of a perspective projection
+    /// with the given near and far plane distances, which is linearized before it is mapped to grayscale.
+    /// Without linearization, almost all of the scene would be close to white, since the precision of a depth buffer is concentrated near the camera.
+    ///
+    pub fn visualize_depth(&self, near: f32, far: f32) -> Texture2D {
+        Texture2D {
+            data: TextureData::RU8(
+                self.data
+                    .iter_rgba_f32()
+                    .map(|c| {
+                        let linear = near * far / (far - c[0].clamp(0.0, 1.0) * (far - near));
+                        let gray = (linear - near) / (far - near);
+                        (gray.clamp(0.0, 1.0) * 255.0).round() as u8
+                    })
+                    .collect(),
+            ),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns the normalized luminance of each texel in the same order as the [TextureData], for example to use the texture as a heightmap or depth data.
+    /// One and two channel data is used directly as luminance, while the luminance of RGB data is computed using the Rec. 709 weights.
+    /// The alpha channel is ignored, also for RGBA data.
+    ///
+    pub fn height_f32(&self) -> Vec<f32> {
+        self.data.iter_rgba_f32().map(luminance).collect()
+    }
+
+    ///
+    /// Returns a grayscale and alpha copy of this texture with [TextureData::RgU8] data, for example to use as a UI mask.
+    /// The first channel contains the luminance, computed in the same way as in [Texture2D::height_f32],
+    /// and the second channel contains the alpha value or 255 if this texture has no alpha channel.
+    ///
+    pub fn to_luminance_alpha(&self) -> Texture2D {
+        Texture2D {
+            data: TextureData::RgU8(
+                self.data
+                    .iter_rgba_f32()
+                    .map(|c| {
+                        [luminance(c), c[3]].map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+                    })
+                    .collect(),
+            ),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns the normalized texel values as a flat buffer with exactly the given number of channels per texel in the same order as the [TextureData],
+    /// for example to feed the texture to a machine learning model or a compute shader.
+    /// The values are first converted to RGBA in the same way as [TextureData::iter_rgba_f32], so one channel data is replicated to red, green and blue
+    /// and a missing alpha channel is filled with `1.0`. Then the channels are selected as follows:
+    /// - 1 channel: red (or luminance)
+    /// - 2 channels: red (or luminance) and alpha
+    /// - 3 channels: red, green and blue
+    /// - 4 channels: red, green, blue and alpha
+    /// - more than 4 channels: red, green, blue and alpha followed by zeros
+    ///
+    pub fn to_f32_buffer(&self, channels: usize) -> Vec<f32> {
+        let mut buffer = Vec::with_capacity(self.data.len() * channels);
+        for c in self.data.iter_rgba_f32() {
+            match channels {
+                0 => {}
+                1 => buffer.push(c[0]),
+                2 => buffer.extend_from_slice(&[c[0], c[3]]),
+                3 => buffer.extend_from_slice(&c[..3]),
+                _ => {
+                    buffer.extend_from_slice(&c);
+                    buffer.resize(buffer.len() + channels - 4, 0.0);
+                }
+            }
+        }
+        buffer
+    }
+
+    ///
+    /// Returns a textual preview of this texture with the given number of columns and rows, which is useful for debugging and logging.
+    /// The luminance of the texels is averaged within each cell of the grid and mapped to a character in the ramp `" .:-=+*#%@"`,
+    /// where a space is black and `@` is white. Each row is terminated by a newline.
+    ///
+    pub fn ascii_preview(&self, cols: u32, rows: u32) -> String {
+        const RAMP: &[u8] = b" .:-=+*#%@";
+        let mut preview = String::with_capacity(((cols + 1) * rows) as usize);
+        for row in 0..rows {
+            let y0 = row * self.height / rows;
+            let y1 = ((row + 1) * self.height / rows)
+                .max(y0 + 1)
+                .min(self.height);
+            for col in 0..cols {
+                let x0 = col * self.width / cols;
+                let x1 = ((col + 1) * self.width / cols).max(x0 + 1).min(self.width);
+                let mut sum = 0.0;
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        sum += luminance(self.data.rgba_f32((y * self.width + x) as usize));
+                    }
+                }
+                let luminance = sum / ((x1 - x0) * (y1 - y0)) as f32;
+                let i = (luminance.clamp(0.0, 1.0) * (RAMP.len() - 1) as f32).round() as usize;
+                preview.push(RAMP[i] as char);
+            }
+            preview.push('\n');
+        }
+        preview
+    }
+
+    ///
+    /// Returns an iterator over all texels as 8-bit RGBA values.
+    ///
+    pub(crate) fn rgba_u8_texels(&self) -> impl Iterator<Item = [u8; 4]> + '_ {
+        self.data
+            .iter_rgba_f32()
+            .map(|c| c.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8))
+    }
+
+    ///
+    /// Returns the premultiplied color of the texel with the given index.
+    ///
+    fn premultiplied_texel_at(&self, index: usize) -> [f32; 4] {
+        let color = self.data.rgba_f32(index);
+        if self.premultiplied {
+            color
+        } else {
+            premultiply(color)
+        }
+    }
+
+    ///
+    /// Returns the premultiplied color at the given pixel position, which is wrapped according to the given wrapping modes.
+    ///
+    fn premultiplied_texel(&self, x: i64, y: i64, wrap_s: Wrapping, wrap_t: Wrapping) -> [f32; 4] {
+        let x = wrap(x, self.width, wrap_s);
+        let y = wrap(y, self.height, wrap_t);
+        self.premultiplied_texel_at((y * self.width + x) as usize)
+    }
+
+    ///
+    /// Interpolates the premultiplied colors at the given pixel position where the pixel centers are at integer coordinates.
+    /// Returns transparent black if the texture is empty, since there are no texels to wrap the position to.
+    ///
+    fn interpolate(
+        &self,
+        x: f32,
+        y: f32,
+        interpolation: Interpolation,
+        wrap_s: Wrapping,
+        wrap_t: Wrapping,
+    ) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+        match interpolation {
+            Interpolation::Nearest => {
+                self.premultiplied_texel(x.round() as i64, y.round() as i64, wrap_s, wrap_t)
+            }
+            Interpolation::Linear | Interpolation::CubicSpline => {
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+                let c00 = self.premultiplied_texel(x0, y0, wrap_s, wrap_t);
+                let c10 = self.premultiplied_texel(x0 + 1, y0, wrap_s, wrap_t);
+                let c01 = self.premultiplied_texel(x0, y0 + 1, wrap_s, wrap_t);
+                let c11 = self.premultiplied_texel(x0 + 1, y0 + 1, wrap_s, wrap_t);
+                let mut color = [0.0; 4];
+                for i in 0..4 {
+                    let top = c00[i] + (c10[i] - c00[i]) * tx;
+                    let bottom = c01[i] + (c11[i] - c01[i]) * tx;
+                    color[i] = top + (bottom - top) * ty;
+                }
+                color
+            }
+        }
+    }
+}
+
+fn luminance(c: [f32; 4]) -> f32 {
+    if c[0] == c[1] && c[1] == c[2] {
+        c[0]
+    } else {
+        0.2126 * c[0] + 0.7152 * c[1] + 0.0722 * c[2]
+    }
+}
+
+///
+/// Returns the squared distance transform of the given sampled function,
+/// ie. the minimum of `(i - j)^2 + f[j]` over all `j` for each `i`, using the lower envelope of parabolas.
+///
+fn distance_transform_1d(f: &[f32]) -> Vec<f32> {
+    let n = f.len();
+    let mut result = vec![f32::INFINITY; n];
+    // The locations of the parabolas in the lower envelope and the boundaries between them.
+    let mut locations: Vec<usize> = Vec::with_capacity(n);
+    let mut boundaries: Vec<f32> = Vec::with_capacity(n + 1);
+    for q in (0..n).filter(|q| f[*q].is_finite()) {
+        loop {
+            match locations.last() {
+                Some(&p) => {
+                    let s = ((f[q] + (q * q) as f32) - (f[p] + (p * p) as f32))
+                        / (2.0 * (q - p) as f32);
+                    if s <= *boundaries.last().unwrap() {
+                        locations.pop();
+                        boundaries.pop();
+                    } else {
+                        locations.push(q);
+                        boundaries.push(s);
+                        break;
+                    }
+                }
+                None => {
+                    locations.push(q);
+                    boundaries.push(f32::NEG_INFINITY);
+                    break;
+                }
+            }
+        }
+    }
+    let mut k = 0;
+    for (i, result) in result.iter_mut().enumerate() {
+        if locations.is_empty() {
+            break;
+        }
+        while k + 1 < locations.len() && boundaries[k + 1] < i as f32 {
+            k += 1;
+        }
+        let p = locations[k];
+        *result = (i as f32 - p as f32).powi(2) + f[p];
+    }
+    result
+}
+
+///
+/// Reinterprets the flat values as texels of `N` values each, which avoids copying if the capacity of the vector is also a multiple of `N`.
+/// Each row starts `stride` values after the previous one and any padding at the end of a row is removed.
+///
+fn cast_texels<T: bytemuck::Pod, const N: usize>(
+    width: u32,
+    height: u32,
+    stride: usize,
+    mut values: Vec<T>,
+) -> crate::Result<Vec<[T; N]>> {
+    let row_length = width as usize * N;
+    if stride < row_length {
+        return Err(crate::Error::InvalidBufferLength(
+            "row".to_owned(),
+            row_length,
+            stride,
+        ));
+    }
+    let expected = stride * height as usize;
+    if values.len() != expected {
+        return Err(crate::Error::InvalidBufferLength(
+            "texture".to_owned(),
+            expected,
+            values.len(),
+        ));
+    }
+    if stride != row_length {
+        values = values
+            .chunks(stride)
+            .flat_map(|row| &row[..row_length])
+            .copied()
+            .collect();
+    }
+    Ok(bytemuck::try_cast_vec(values)
+        .unwrap_or_else(|(_, values)| bytemuck::cast_slice(&values).to_vec()))
+}
+
+///
+/// Returns the source texel indices and normalized weights for each texel when downsampling a row or column of texels to the given size.
+///
+fn downsample_weights(
+    kernel: DownsampleKernel,
+    size: usize,
+    level_size: usize,
+    wrapping: Wrapping,
+) -> Vec<Vec<(usize, f32)>> {
+    let step = (size / level_size) as f32;
+    let radius = match kernel {
+        DownsampleKernel::Box => 0.5,
+        DownsampleKernel::Triangle => 1.0,
+        DownsampleKernel::Kaiser | DownsampleKernel::Lanczos => 3.0,
+    };
+    (0..level_size)
+        .map(|i| {
+            if size == level_size {
+                return vec![(i, 1.0)];
+            }
+            // The distance to the center is measured in texels of the downsampled level.
+            let center = (i as f32 + 0.5) * step;
+            let first = (center - radius * step).floor() as i64;
+            let last = (center + radius * step).ceil() as i64;
+            let mut taps: Vec<(usize, f32)> = (first..last)
+                .map(|j| {
+                    let x = (j as f32 + 0.5 - center) / step;
+                    (
+                        wrap(j, size as u32, wrapping) as usize,
+                        kernel_weight(kernel, x, radius),
+                    )
+                })
+                .filter(|(_, weight)| *weight != 0.0)
+                .collect();
+            let sum: f32 = taps.iter().map(|(_, weight)| weight).sum();
+            for (_, weight) in taps.iter_mut() {
+                *weight /= sum;
+            }
+            taps
+        })
+        .collect()
+}
+
+fn kernel_weight(kernel: DownsampleKernel, x: f32, radius: f32) -> f32 {
+    let sinc = |x: f32| {
+        if x == 0.0 {
+            1.0
+        } else {
+            let x = x * std::f32::consts::PI;
+            x.sin() / x
+        }
+    };
+    if x.abs() >= radius {
+        return 0.0;
+    }
+    match kernel {
+        DownsampleKernel::Box => 1.0,
+        DownsampleKernel::Triangle => 1.0 - x.abs(),
+        DownsampleKernel::Kaiser => {
+            const ALPHA: f32 = 4.0;
+            let t = x / radius;
+            sinc(x) * bessel_i0(ALPHA * (1.0 - t * t).sqrt()) / bessel_i0(ALPHA)
+        }
+        DownsampleKernel::Lanczos => sinc(x) * sinc(x / radius),
+    }
+}
+
+///
+/// The zeroth order modified Bessel function of the first kind, used by the Kaiser window.
+///
+fn bessel_i0(x: f32) -> f32 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut k = 1.0;
+    while term > sum * 1e-7 {
+        term *= (x / (2.0 * k)) * (x / (2.0 * k));
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
+
+fn wrap(coord: i64, size: u32, wrapping: Wrapping) -> u32 {
+    let size = size as i64;
+    (match wrapping {
+        Wrapping::Repeat => coord.rem_euclid(size),
+        Wrapping::MirroredRepeat => {
+            let c = coord.rem_euclid(2 * size);
+            if c < size {
+                c
+            } else {
+                2 * size - 1 - c
+            }
+        }
+        Wrapping::ClampToEdge => coord.clamp(0, size - 1),
+    }) as u32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tex() -> Texture2D {
+        Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 0, 0, 255], [0, 0, 0, 0]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn premultiplied_flag() {
+        let mut tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[200, 100, 50, 128]]),
+            width: 1,
+            height: 1,
+            ..Default::default()
+        };
+        assert!(!tex.premultiplied);
+        tex.premultiply_alpha();
+        assert!(tex.premultiplied);
+        assert_eq!(tex.data, TextureData::RgbaU8(vec![[100, 50, 25, 128]]));
+        assert!(tex.resize(2, 2, Interpolation::Linear).premultiplied);
+        tex.unpremultiply_alpha();
+        assert!(!tex.premultiplied);
+        assert!(!tex.resize(2, 2, Interpolation::Linear).premultiplied);
+    }
+
+    #[test]
+    pub fn resize_to_fit() {
+        let tex = Texture2D {
+            data: TextureData::RgbU8(vec![[10, 20, 30]; 100 * 50]),
+            width: 100,
+            height: 50,
+            ..Default::default()
+        };
+        let fitted = tex.resize_to_fit(40, 40, Interpolation::Linear, false);
+        assert_eq!((fitted.width, fitted.height), (40, 20));
+        assert_eq!(fitted.data, TextureData::RgbU8(vec![[10, 20, 30]; 40 * 20]));
+
+        let fitted = tex.resize_to_fit(100, 10, Interpolation::Nearest, true);
+        assert_eq!((fitted.width, fitted.height), (20, 10));
+
+        let fitted = tex.resize_to_fit(400, 400, Interpolation::Linear, false);
+        assert_eq!((fitted.width, fitted.height), (100, 50));
+
+        let fitted = tex.resize_to_fit(400, 400, Interpolation::Linear, true);
+        assert_eq!((fitted.width, fitted.height), (400, 200));
+        assert_eq!(
+            fitted.data,
+            TextureData::RgbU8(vec![[10, 20, 30]; 400 * 200])
+        );
+    }
+
+    #[test]
+    pub fn thumbnail() {
+        let tex = Texture2D::from_fn(1000, 500, |x, _| {
+            if x % 2 == 0 {
+                [255, 255, 255, 255]
+            } else {
+                [0, 0, 0, 255]
+            }
+        });
+        let thumbnail = tex.thumbnail(256);
+        assert_eq!((thumbnail.width, thumbnail.height), (256, 128));
+        // Every texel covers a block of three or four black and white columns, which averages to gray.
+        let TextureData::RgbaU8(data) = &thumbnail.data else {
+            panic!("expected RgbaU8 data");
+        };
+        assert!(data
+            .iter()
+            .all(|c| (64..=192).contains(&c[0]) && c[3] == 255));
+
+        let portrait = Texture2D::from_fn(10, 40, |_, _| [10, 20, 30, 40]).thumbnail(8);
+        assert_eq!((portrait.width, portrait.height), (2, 8));
+        assert_eq!(
+            portrait.data,
+            TextureData::RgbaU8(vec![[10, 20, 30, 40]; 16])
+        );
+        assert_eq!(tex.thumbnail(2000).dimensions(), (1000, 500));
+
+        let empty = Texture2D {
+            data: TextureData::RgbaU8(Vec::new()),
+            width: 0,
+            height: 10,
+            ..Default::default()
+        };
+        assert_eq!(empty.thumbnail(5), empty);
+    }
+
+    #[test]
+    pub fn resize_does_not_darken_edges() {
+        let resized = tex().resize(3, 1, Interpolation::Linear);
+        assert_eq!(
+            resized.data,
+            TextureData::RgbaU8(vec![[255, 0, 0, 255], [255, 0, 0, 128], [0, 0, 0, 0]])
+        );
+
+        let mut tex = tex();
+        tex.premultiply_alpha();
+        let mut resized = tex.resize(3, 1, Interpolation::Linear);
+        assert_eq!(
+            resized.data,
+            TextureData::RgbaU8(vec![[255, 0, 0, 255], [128, 0, 0, 128], [0, 0, 0, 0]])
+        );
+        resized.unpremultiply_alpha();
+        assert_eq!(
+            resized.data,
+            TextureData::RgbaU8(vec![[255, 0, 0, 255], [255, 0, 0, 128], [0, 0, 0, 0]])
+        );
+    }
+
+    #[test]
+    pub fn to_premultiplied_rgba_f16() {
+        use crate::f16;
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 51, 0, 128], [255, 255, 255, 255]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        let result = tex.to_premultiplied_rgba_f16();
+        assert!(result.premultiplied);
+        let a = 128.0 / 255.0;
+        assert_eq!(
+            result.data,
+            TextureData::RgbaF16(vec![[a, 0.2 * a, 0.0, a].map(f16::from_f32), [f16::ONE; 4]])
+        );
+        assert_eq!(result.to_premultiplied_rgba_f16().data, result.data);
+    }
+
+    #[test]
+    pub fn convert() {
+        use crate::f16;
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 51, 0, 128]]),
+            width: 1,
+            height: 1,
+            ..Default::default()
+        };
+        let converted = tex.convert(TextureFormat::Rgb16F);
+        assert_eq!(
+            converted.data,
+            TextureData::RgbF16(vec![[1.0, 0.2, 0.0].map(f16::from_f32)])
+        );
+        assert_eq!(converted.data.format(), TextureFormat::Rgb16F);
+
+        let tex = Texture2D {
+            data: TextureData::RU8(vec![0, 51, 255]),
+            width: 3,
+            height: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            tex.convert(TextureFormat::Rgba32F).data,
+            TextureData::RgbaF32(vec![
+                [0.0, 0.0, 0.0, 1.0],
+                [0.2, 0.2, 0.2, 1.0],
+                [1.0, 1.0, 1.0, 1.0]
+            ])
+        );
+        assert_eq!(tex.convert(TextureFormat::R8).data, tex.data);
+
+        let mut tex = tex.convert(TextureFormat::Rg8);
+        tex.premultiply_alpha();
+        assert!(tex.convert(TextureFormat::Rgba16).premultiplied);
+        assert!(!tex.convert(TextureFormat::Rgb16).premultiplied);
+    }
+
+    #[test]
+    pub fn to_rgb_f16() {
+        use crate::f16;
+        let values = [0.1234, 2.5, 1000.25];
+        let tex = Texture2D {
+            data: TextureData::RgbF32(vec![values]),
+            width: 1,
+            height: 1,
+            ..Default::default()
+        };
+        let TextureData::RgbF16(data) = tex.to_rgb_f16().data else {
+            panic!("expected RgbF16 data");
+        };
+        for (value, half) in values.into_iter().zip(data[0]) {
+            // Half precision floats have an 11-bit significand.
+            assert!((half.to_f32() - value).abs() <= value * 2f32.powi(-11));
+        }
+
+        let tex = Texture2D {
+            data: TextureData::RgU8(vec![[51, 0]]),
+            width: 1,
+            height: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            tex.to_rgb_f16().data,
+            TextureData::RgbF16(vec![[f16::from_f32(0.2); 3]])
+        );
+    }
+
+    #[test]
+    pub fn to_rgba_u8_dithered() {
+        // A gradient which is less than one 8-bit step wide.
+        let values: Vec<f32> = (0..64)
+            .map(|i| (127.6 + 0.8 * (i % 16) as f32 / 15.0) / 255.0)
+            .collect();
+        let tex = Texture2D {
+            data: TextureData::RF32(values.clone()),
+            width: 16,
+            height: 4,
+            ..Default::default()
+        };
+        let distinct = |data: &TextureData| match data {
+            TextureData::RgbaU8(data) => {
+                let mut reds: Vec<u8> = data.iter().map(|c| c[0]).collect();
+                reds.sort();
+                reds.dedup();
+                reds.len()
+            }
+            _ => unreachable!(),
+        };
+        let rounded = TextureData::RgbaU8(tex.rgba_u8_texels().collect());
+        let dithered = tex.to_rgba_u8_dithered().data;
+        assert_eq!(distinct(&rounded), 1);
+        assert!(distinct(&dithered) > distinct(&rounded));
+
+        // The average of the dithered values is close to the average of the float values.
+        if let TextureData::RgbaU8(data) = dithered {
+            let average = data.iter().map(|c| c[0] as f32).sum::<f32>() / 64.0;
+            let expected = values.iter().sum::<f32>() * 255.0 / 64.0;
+            assert!((average - expected).abs() < 0.1);
+            assert!(data.iter().all(|c| c[3] == 255));
+        }
+    }
+
+    #[test]
+    pub fn sample() {
+        let tex = Texture2D {
+            wrap_s: Wrapping::ClampToEdge,
+            ..tex()
+        };
+        let color = tex.sample(0.5, 0.5);
+        assert_eq!(color, [1.0, 0.0, 0.0, 0.5]);
+        let mut tex = tex;
+        tex.premultiply_alpha();
+        let color = tex.sample(0.5, 0.5);
+        assert_eq!(color, [0.5, 0.0, 0.0, 0.5]);
+
+        // An empty texture can be sampled and resampled without any texels.
+        let empty = Texture2D {
+            data: TextureData::RgbaU8(Vec::new()),
+            width: 0,
+            height: 3,
+            ..Default::default()
+        };
+        assert_eq!(empty.sample(0.5, 0.5), [0.0; 4]);
+        assert_eq!(
+            empty.resize(2, 1, Interpolation::Linear).data,
+            TextureData::RgbaU8(vec![[0; 4]; 2])
+        );
+        let rotated = empty.rotate(0.5, Interpolation::Nearest, [0.0; 4]);
+        assert!(rotated.rgba_u8_texels().all(|c| c == [0; 4]));
+        assert_eq!(empty.extend_edges(1).width, 2);
+    }
+
+    #[test]
+    pub fn sample_with_border() {
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 0, 0, 255], [0, 0, 255, 255]]),
+            width: 2,
+            height: 1,
+            wrap_s: Wrapping::Repeat,
+            ..Default::default()
+        };
+        let border = [0.0, 1.0, 0.0, 1.0];
+        assert_eq!(tex.sample_with_border(-0.001, 0.5, border), border);
+        assert_eq!(
+            tex.sample_with_border(0.0, 0.5, border),
+            [1.0, 0.0, 0.0, 1.0]
+        );
+        assert_eq!(
+            tex.sample_with_border(0.5, 0.5, border),
+            [0.5, 0.0, 0.5, 1.0]
+        );
+        assert_eq!(
+            tex.sample_with_border(1.0, 0.5, border),
+            [0.0, 0.0, 1.0, 1.0]
+        );
+        assert_eq!(tex.sample_with_border(1.001, 0.5, border), border);
+        assert_eq!(tex.sample_with_border(0.5, -0.001, border), border);
+        assert_eq!(tex.sample_with_border(0.5, 1.001, border), border);
+        assert_eq!(tex.sample_with_border(f32::NAN, 0.5, border), border);
+        // The repeat wrapping would otherwise blend in the texel on the opposite edge.
+        assert_eq!(tex.sample(0.0, 0.5), [0.5, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    pub fn rotate() {
+        let background = [0.0, 0.0, 1.0, 1.0];
+        let tex = Texture2D::from_fn(8, 8, |_, _| [255, 0, 0, 255]);
+        let rotated = tex.rotate(
+            std::f32::consts::FRAC_PI_4,
+            Interpolation::Linear,
+            background,
+        );
+        // The diagonal of the square is 8 * sqrt(2) = 11.3 texels.
+        assert_eq!((rotated.width, rotated.height), (12, 12));
+        let TextureData::RgbaU8(data) = &rotated.data else {
+            panic!("expected RgbaU8 data");
+        };
+        for corner in [0, 11, 11 * 12, 12 * 12 - 1] {
+            assert_eq!(data[corner], [0, 0, 255, 255]);
+        }
+        assert_eq!(data[6 * 12 + 6], [255, 0, 0, 255]);
+        assert_eq!(data[12 + 6], [255, 0, 0, 255]);
+
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 0, 255]]),
+            width: 3,
+            height: 1,
+            ..Default::default()
+        };
+        let rotated = tex.rotate(
+            std::f32::consts::FRAC_PI_2,
+            Interpolation::Nearest,
+            background,
+        );
+        assert_eq!((rotated.width, rotated.height), (1, 3));
+        assert_eq!(
+            rotated.data,
+            TextureData::RgbaU8(vec![[0, 0, 0, 255], [0, 255, 0, 255], [255, 0, 0, 255]])
+        );
+    }
+
+    #[test]
+    pub fn blit() {
+        let mut target = Texture2D {
+            data: TextureData::RgbaU8(vec![[0, 0, 0, 0]; 4]),
+            width: 2,
+            height: 2,
+            premultiplied: true,
+            ..Default::default()
+        };
+        let source = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 255, 255, 128], [255, 255, 255, 128]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        target.blit(&source, 1, 1);
+        assert_eq!(
+            target.data,
+            TextureData::RgbaU8(vec![
+                [0, 0, 0, 0],
+                [0, 0, 0, 0],
+                [0, 0, 0, 0],
+                [128, 128, 128, 128]
+            ])
+        );
+    }
+
+    #[test]
+    pub fn fill_rect() {
+        let mut tex = Texture2D::from_fn(4, 4, |_, _| [0, 0, 0, 255]);
+        tex.fill_rect(1, 2, 2, 5, [255, 0, 0, 255]);
+        tex.fill_rect(-2, -2, 3, 3, [0, 255, 0, 255]);
+        let TextureData::RgbaU8(data) = &tex.data else {
+            panic!("expected RgbaU8 data");
+        };
+        let red: Vec<usize> = (0..16).filter(|i| data[*i] == [255, 0, 0, 255]).collect();
+        assert_eq!(red, vec![9, 10, 13, 14]);
+        assert_eq!(data[0], [0, 255, 0, 255]);
+        assert_eq!(data[1], [0, 0, 0, 255]);
+
+        let mut tex = Texture2D::from_fn_f32(2, 1, |_, _| [0.0; 4]);
+        tex.fill_rect(1, 0, 1, 1, [255, 0, 0, 255]);
+        assert_eq!(
+            tex.data,
+            TextureData::RgbaF32(vec![[0.0; 4], [1.0, 0.0, 0.0, 1.0]])
+        );
+    }
+
+    #[test]
+    pub fn draw_line() {
+        let mut tex = Texture2D::from_fn(4, 4, |_, _| [0, 0, 0, 0]);
+        tex.draw_line(0, 0, 3, 3, [255; 4]);
+        let TextureData::RgbaU8(data) = &tex.data else {
+            panic!("expected RgbaU8 data");
+        };
+        let drawn: Vec<usize> = (0..16).filter(|i| data[*i] == [255; 4]).collect();
+        assert_eq!(drawn, vec![0, 5, 10, 15]);
+
+        let mut tex = Texture2D::from_fn(4, 4, |_, _| [0, 0, 0, 0]);
+        tex.draw_line(-2, 1, 10, 1, [255; 4]);
+        tex.draw_line(3, 3, 3, 3, [255; 4]);
+        let TextureData::RgbaU8(data) = &tex.data else {
+            panic!("expected RgbaU8 data");
+        };
+        let drawn: Vec<usize> = (0..16).filter(|i| data[*i] == [255; 4]).collect();
+        assert_eq!(drawn, vec![4, 5, 6, 7, 15]);
+    }
+
+    #[test]
+    pub fn content_bounds() {
+        let mut data = vec![[0, 0, 0, 0]; 36];
+        for y in 2..4 {
+            for x in 2..4 {
+                data[y * 6 + x] = [255, 0, 0, 255];
+            }
+        }
+        data[6 * 6 - 1] = [255, 0, 0, 10];
+        let mut tex = Texture2D {
+            data: TextureData::RgbaU8(data),
+            width: 6,
+            height: 6,
+            ..Default::default()
+        };
+        assert_eq!(tex.content_bounds(10), Some((2, 2, 2, 2)));
+        assert_eq!(tex.content_bounds(0), Some((2, 2, 4, 4)));
+
+        tex.data = TextureData::RgbaU8(vec![[255, 255, 255, 0]; 36]);
+        assert_eq!(tex.content_bounds(0), None);
+
+        tex.data = TextureData::RgbU8(vec![[255, 255, 255]; 36]);
+        assert_eq!(tex.content_bounds(0), None);
+    }
+
+    #[test]
+    pub fn write_region() {
+        let mut tex = Texture2D {
+            data: TextureData::RU8((0..16).collect()),
+            width: 4,
+            height: 4,
+            ..Default::default()
+        };
+        let region = Texture2D {
+            data: TextureData::RU8(vec![100, 101, 102, 103]),
+            width: 2,
+            height: 2,
+            ..Default::default()
+        };
+        tex.write_region(1, 1, &region).unwrap();
+        assert_eq!(
+            tex.data,
+            TextureData::RU8(vec![
+                0, 1, 2, 3, 4, 100, 101, 7, 8, 102, 103, 11, 12, 13, 14, 15
+            ])
+        );
+
+        assert!(matches!(
+            tex.write_region(3, 1, &region),
+            Err(crate::Error::RegionOutOfBounds(..))
+        ));
+        let region = Texture2D {
+            data: TextureData::RgbaU8(vec![[0; 4]; 4]),
+            ..region
+        };
+        assert!(matches!(
+            tex.write_region(1, 1, &region),
+            Err(crate::Error::TextureDataFormatMismatch)
+        ));
+    }
+
+    #[test]
+    pub fn reorient() {
+        // 3x2 texture with a marker in the top left corner
+        let tex = Texture2D {
+            data: TextureData::RU8(vec![9, 1, 2, 3, 4, 5]),
+            width: 3,
+            height: 2,
+            ..Default::default()
+        };
+        let check = |orientation, width, height, expected: Vec<u8>| {
+            let result = tex.reorient(orientation);
+            assert_eq!((result.width, result.height), (width, height));
+            assert_eq!(result.data, TextureData::RU8(expected));
+        };
+        check(TextureOrientation::Identity, 3, 2, vec![9, 1, 2, 3, 4, 5]);
+        check(
+            TextureOrientation::FlipHorizontal,
+            3,
+            2,
+            vec![2, 1, 9, 5, 4, 3],
+        );
+        check(
+            TextureOrientation::FlipVertical,
+            3,
+            2,
+            vec![3, 4, 5, 9, 1, 2],
+        );
+        check(TextureOrientation::Rotate90, 2, 3, vec![3, 9, 4, 1, 5, 2]);
+        check(TextureOrientation::Rotate180, 3, 2, vec![5, 4, 3, 2, 1, 9]);
+        check(TextureOrientation::Rotate270, 2, 3, vec![2, 5, 1, 4, 9, 3]);
+    }
+
+    #[test]
+    pub fn from_raw() {
+        let bytes = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let tex = Texture2D::from_raw_rgba(2, 1, bytes).unwrap();
+        assert_eq!((tex.width, tex.height), (2, 1));
+        assert_eq!(
+            tex.data,
+            TextureData::RgbaU8(vec![[1, 2, 3, 4], [5, 6, 7, 8]])
+        );
+
+        let mut bytes = Vec::with_capacity(7);
+        bytes.extend_from_slice(&[1, 2, 3, 4, 5, 6]);
+        let tex = Texture2D::from_raw_rgb(1, 2, bytes).unwrap();
+        assert_eq!(tex.data, TextureData::RgbU8(vec![[1, 2, 3], [4, 5, 6]]));
+
+        let tex = Texture2D::from_raw_rgba_f32(1, 1, vec![0.5, 0.25, 0.0, 1.0]).unwrap();
+        assert_eq!(tex.data, TextureData::RgbaF32(vec![[0.5, 0.25, 0.0, 1.0]]));
+
+        assert!(matches!(
+            Texture2D::from_raw_rgba(2, 2, vec![0; 8]),
+            Err(crate::Error::InvalidBufferLength(_, 16, 8))
+        ));
+        assert!(matches!(
+            Texture2D::from_raw_rgba(2, 2, vec![0; 17]),
+            Err(crate::Error::InvalidBufferLength(_, 16, 17))
+        ));
+        assert!(matches!(
+            Texture2D::from_raw_rgba(2, 2, vec![0; 32]),
+            Err(crate::Error::InvalidBufferLength(_, 16, 32))
+        ));
+        let tex = Texture2D::from_raw_rgba_with_stride(1, 2, 8, (0..16).collect()).unwrap();
+        assert_eq!(
+            tex.data,
+            TextureData::RgbaU8(vec![[0, 1, 2, 3], [8, 9, 10, 11]])
+        );
+    }
+
+    #[test]
+    pub fn from_fn() {
+        // A radial gradient which is white in the center and black at a distance of 8 texels.
+        let distance = |x: u32, y: u32| {
+            let (dx, dy) = (x as f32 + 0.5 - 8.0, y as f32 + 0.5 - 8.0);
+            ((dx * dx + dy * dy).sqrt() / 8.0).min(1.0)
+        };
+        let tex = Texture2D::from_fn(16, 16, |x, y| {
+            let v = ((1.0 - distance(x, y)) * 255.0).round() as u8;
+            [v, v, v, 255]
+        });
+        assert_eq!((tex.width, tex.height), (16, 16));
+        let TextureData::RgbaU8(data) = &tex.data else {
+            panic!("expected RgbaU8 data");
+        };
+        assert!(data[8 * 16 + 8][0] > 224);
+        assert_eq!(data[7 * 16 + 7], data[8 * 16 + 8]);
+        assert_eq!(data[0], [0, 0, 0, 255]);
+        assert_eq!(data[15 * 16 + 15], [0, 0, 0, 255]);
+
+        let tex = Texture2D::from_fn_f32(16, 16, |x, y| {
+            let v = 1.0 - distance(x, y);
+            [v, v, v, 1.0]
+        });
+        let TextureData::RgbaF32(data) = &tex.data else {
+            panic!("expected RgbaF32 data");
+        };
+        assert!(data[8 * 16 + 8][0] > data[8 * 16 + 12][0]);
+        assert_eq!(data[0][0], 0.0);
+    }
+
+    #[test]
+    pub fn from_raw_bytes() {
+        let bytes = [0x12, 0x34, 0xAB, 0xCD];
+        let little =
+            Texture2D::from_raw_bytes(2, 1, TextureFormat::R16, &bytes, Endianness::Little)
+                .unwrap();
+        assert_eq!(little.data, TextureData::RU16(vec![0x3412, 0xCDAB]));
+        let big =
+            Texture2D::from_raw_bytes(2, 1, TextureFormat::R16, &bytes, Endianness::Big).unwrap();
+        assert_eq!(big.data, TextureData::RU16(vec![0x1234, 0xABCD]));
+        assert_eq!((big.width, big.height), (2, 1));
+        assert!(matches!(
+            Texture2D::from_raw_bytes(2, 2, TextureFormat::R16, &bytes, Endianness::Big),
+            Err(crate::Error::InvalidBufferLength(_, 8, 4))
+        ));
+    }
+
+    #[test]
+    pub fn from_yuv420() {
+        let close = |a: [u8; 4], b: [u8; 4]| (0..4).all(|c| (a[c] as i32 - b[c] as i32).abs() <= 1);
+
+        // Pure red with the BT.601 matrix in the top left block, white in the top right block and black below.
+        let y = [81, 81, 235, 235, 81, 81, 235, 235, 16, 16, 16, 16];
+        let u = [90, 128, 128, 128];
+        let v = [240, 128, 128, 128];
+        let tex = Texture2D::from_yuv420(&y, &u, &v, 4, 3, YuvMatrix::Bt601).unwrap();
+        assert_eq!((tex.width, tex.height), (4, 3));
+        let TextureData::RgbaU8(data) = &tex.data else {
+            panic!("expected RgbaU8 data");
+        };
+        for i in [0, 1, 4, 5] {
+            assert!(close(data[i], [255, 0, 0, 255]));
+        }
+        assert_eq!(data[2], [255, 255, 255, 255]);
+        assert_eq!(data[11], [0, 0, 0, 255]);
+
+        // Pure red with the BT.709 matrix.
+        let tex = Texture2D::from_yuv420(&[63], &[102], &[240], 1, 1, YuvMatrix::Bt709).unwrap();
+        let TextureData::RgbaU8(data) = &tex.data else {
+            panic!("expected RgbaU8 data");
+        };
+        assert!(close(data[0], [255, 0, 0, 255]));
+
+        assert!(matches!(
+            Texture2D::from_yuv420(&y, &u, &v[..3], 4, 3, YuvMatrix::Bt601),
+            Err(crate::Error::InvalidBufferLength(_, 4, 3))
+        ));
+    }
+
+    #[test]
+    pub fn from_raw_padded_rows() {
+        // 3x2 RGB rows aligned to 4 bytes, so each row has 9 bytes of pixels and 3 bytes of padding.
+        let bytes = vec![
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 10, 11, 12, 13, 14, 15, 16, 17, 18, 0, 0, 0,
+        ];
+        assert!(matches!(
+            Texture2D::from_raw_rgb(3, 2, bytes.clone()),
+            Err(crate::Error::InvalidBufferLength(_, 18, 24))
+        ));
+        assert!(matches!(
+            Texture2D::from_raw_rgb_with_stride(3, 2, 8, bytes.clone()),
+            Err(crate::Error::InvalidBufferLength(_, 9, 8))
+        ));
+        let tex = Texture2D::from_raw_rgb_with_stride(3, 2, 12, bytes).unwrap();
+        assert_eq!(
+            tex.data,
+            TextureData::RgbU8(vec![
+                [1, 2, 3],
+                [4, 5, 6],
+                [7, 8, 9],
+                [10, 11, 12],
+                [13, 14, 15],
+                [16, 17, 18]
+            ])
+        );
+    }
+
+    #[test]
+    pub fn crop() {
+        let tex = Texture2D {
+            data: TextureData::RU8((0..12).collect()),
+            width: 4,
+            height: 3,
+            ..Default::default()
+        };
+        let cropped = tex.crop(1, 1, 2, 5);
+        assert_eq!((cropped.width, cropped.height), (2, 2));
+        assert_eq!(cropped.data, TextureData::RU8(vec![5, 6, 9, 10]));
+        assert_eq!(tex.trim_transparent(0), None);
+    }
+
+    #[test]
+    pub fn multiple_of() {
+        let tex = Texture2D::from_fn(10, 10, |x, y| [x as u8, y as u8, 0, 255]);
+
+        let cropped = tex.crop_to_multiple_of(4);
+        assert_eq!((cropped.width, cropped.height), (8, 8));
+        assert_eq!(cropped.data, tex.crop(0, 0, 8, 8).data);
+
+        let padded = tex.pad_to_multiple_of(4, [255, 0, 255, 255]);
+        assert_eq!((padded.width, padded.height), (12, 12));
+        assert_eq!(padded.crop(0, 0, 10, 10).data, tex.data);
+        let TextureData::RgbaU8(data) = &padded.data else {
+            panic!("expected RgbaU8 data");
+        };
+        assert_eq!(data[10], [255, 0, 255, 255]);
+        assert_eq!(data[11 * 12], [255, 0, 255, 255]);
+
+        assert_eq!(cropped.pad_to_multiple_of(4, [0; 4]), cropped);
+        assert_eq!(cropped.crop_to_multiple_of(4), cropped);
+    }
+
+    #[test]
+    pub fn builder() {
+        let texture = Texture2D::default()
+            .with_wrapping(Wrapping::ClampToEdge, Wrapping::MirroredRepeat)
+            .with_filters(Interpolation::Nearest, Interpolation::CubicSpline)
+            .with_mipmaps(Some(Interpolation::Nearest));
+        assert_eq!(texture.wrap_s, Wrapping::ClampToEdge);
+        assert_eq!(texture.wrap_t, Wrapping::MirroredRepeat);
+        assert_eq!(texture.min_filter, Interpolation::Nearest);
+        assert_eq!(texture.mag_filter, Interpolation::CubicSpline);
+        assert_eq!(texture.mip_map_filter, Some(Interpolation::Nearest));
+        assert_eq!(texture.data, Texture2D::default().data);
+    }
+
+    #[test]
+    pub fn make_tileable() {
+        // A gradient which does not tile, since the left edge is black and the right edge is white.
+        let tex = Texture2D::from_fn(64, 64, |x, y| [(x * 4) as u8, (y * 4) as u8, 0, 255]);
+        let tileable = tex.make_tileable(8);
+        assert_eq!((tileable.width, tileable.height), (64, 64));
+        let TextureData::RgbaU8(data) = &tileable.data else {
+            panic!("expected RgbaU8 data");
+        };
+        for i in 0..64 {
+            let (left, right) = (data[i * 64], data[i * 64 + 63]);
+            let (top, bottom) = (data[i], data[63 * 64 + i]);
+            for c in 0..4 {
+                assert!((left[c] as i32 - right[c] as i32).abs() <= 4);
+                assert!((top[c] as i32 - bottom[c] as i32).abs() <= 4);
+            }
+        }
+        // The center, where the original edges meet after the offset, is blended with the original texture.
+        let TextureData::RgbaU8(original) = &tex.data else {
+            unreachable!()
+        };
+        assert!((data[32 * 64 + 32][0] as i32 - original[32 * 64 + 32][0] as i32).abs() <= 32);
+
+        assert_eq!(tex.make_tileable(0).data, {
+            let mut offset = vec![[0; 4]; 64 * 64];
+            for (i, texel) in offset.iter_mut().enumerate() {
+                *texel = original[((i / 64 + 32) % 64) * 64 + (i % 64 + 32) % 64];
+            }
+            TextureData::RgbaU8(offset)
+        });
+    }
+
+    #[test]
+    pub fn blur() {
+        let mut data = vec![0.0; 25];
+        data[12] = 1.0;
+        let tex = Texture2D {
+            data: TextureData::RF32(data),
+            width: 5,
+            height: 5,
+            ..Default::default()
+        };
+
+        let blurred = tex.box_blur(1);
+        if let TextureData::RF32(data) = &blurred.data {
+            for y in 0..5 {
+                for x in 0..5 {
+                    let expected = if (1..4).contains(&x) && (1..4).contains(&y) {
+                        1.0 / 9.0
+                    } else {
+                        0.0
+                    };
+                    assert!((data[y * 5 + x] - expected).abs() < 1e-6);
+                }
+            }
+        } else {
+            panic!("Wrong texture data: {:?}", blurred.data)
+        }
+
+        let sigma = 0.5;
+        let blurred = tex.gaussian_blur(sigma);
+        let kernel: Vec<f32> = (-2..=2)
+            .map(|x: i32| (-((x * x) as f32) / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let sum: f32 = kernel.iter().sum();
+        if let TextureData::RF32(data) = &blurred.data {
+            for y in 0..5 {
+                for x in 0..5 {
+                    let expected = kernel[x] * kernel[y] / (sum * sum);
+                    assert!((data[y * 5 + x] - expected).abs() < 1e-6);
+                }
+            }
+            assert!((data.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        } else {
+            panic!("Wrong texture data: {:?}", blurred.data)
+        }
+    }
+
+    #[test]
+    pub fn reduce() {
+        let tex = Texture2D::from_fn(4, 2, |x, y| [(x * 50) as u8, (y * 100) as u8, 0, 255]);
+        let max_luminance = tex.reduce(0.0f32, |max, c| max.max(luminance(c)));
+        let expected = 0.2126 * 150.0 / 255.0 + 0.7152 * 100.0 / 255.0;
+        assert!((max_luminance - expected).abs() < 1e-6);
+
+        let coverage = tex.reduce(0, |count, c| count + (c[0] > 0.0) as usize);
+        assert_eq!(coverage, 6);
+        assert_eq!(
+            Texture2D::from_fn(0, 0, |_, _| [0; 4]).reduce(1, |n, _| n + 1),
+            1
+        );
+    }
+
+    #[test]
+    pub fn average_color() {
+        let tex = Texture2D::from_fn(4, 4, |_, _| [255, 51, 0, 255]);
+        assert_eq!(tex.average_color(false), [1.0, 0.2, 0.0, 1.0]);
+        assert_eq!(tex.average_color(true), [1.0, 0.2, 0.0, 1.0]);
+
+        // The left half is transparent black and the right half is opaque red.
+        let tex = Texture2D::from_fn(4, 4, |x, _| {
+            if x < 2 {
+                [0, 0, 0, 0]
+            } else {
+                [255, 0, 0, 255]
+            }
+        });
+        assert_eq!(tex.average_color(true), [1.0, 0.0, 0.0, 0.5]);
+        assert_eq!(tex.average_color(false), [0.5, 0.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    pub fn strip_redundant_alpha() {
+        let mut tex = Texture2D::from_fn(2, 2, |x, y| [x as u8, y as u8, 7, 255]);
+        assert!(tex.strip_redundant_alpha());
+        assert_eq!(
+            tex.data,
+            TextureData::RgbU8(vec![[0, 0, 7], [1, 0, 7], [0, 1, 7], [1, 1, 7]])
+        );
+        assert!(!tex.strip_redundant_alpha());
+
+        let mut tex = Texture2D::from_fn(2, 2, |x, _| [0, 0, 0, 255 - x as u8]);
+        let original = tex.clone();
+        assert!(!tex.strip_redundant_alpha());
+        assert_eq!(tex, original);
+
+        let mut tex = Texture2D::from_fn_f32(2, 1, |_, _| [0.5, 0.25, 2.0, 1.0]);
+        assert!(tex.strip_redundant_alpha());
+        assert_eq!(tex.data, TextureData::RgbF32(vec![[0.5, 0.25, 2.0]; 2]));
+
+        let mut tex = Texture2D {
+            data: TextureData::RgU16(vec![[100, u16::MAX]]),
+            ..Default::default()
+        };
+        assert!(tex.strip_redundant_alpha());
+        assert_eq!(tex.data, TextureData::RU16(vec![100]));
+    }
+
+    #[test]
+    pub fn palette() {
+        let colors = [
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [0, 0, 255, 255],
+            [255, 255, 255, 255],
+        ];
+        let tex = Texture2D {
+            data: TextureData::RgbaU8((0..16).map(|i| colors[(i % 4 + i / 4) % 4]).collect()),
+            width: 4,
+            height: 4,
+            ..Default::default()
+        };
+        assert_eq!(tex.distinct_colors(), 4);
+
+        let palette = tex.extract_palette(4);
+        assert_eq!(palette.len(), 4);
+        for color in colors {
+            assert!(palette.contains(&color));
+        }
+        assert_eq!(tex.extract_palette(16), palette);
+        assert_eq!(tex.extract_palette(2).len(), 2);
+        assert_eq!(tex.extract_palette(0).len(), 0);
+
+        let empty = Texture2D {
+            data: TextureData::RgbaU8(Vec::new()),
+            width: 0,
+            height: 0,
+            ..Default::default()
+        };
+        assert_eq!(empty.extract_palette(4), Vec::<[u8; 4]>::new());
+    }
+
+    #[test]
+    pub fn depth16() {
+        let (near, far) = (0.1, 100.0);
+        // The depth buffer values of a perspective projection at the given distances from the camera.
+        let distances = [0.1, 25.075, 50.05, 75.025, 100.0];
+        let depth: Vec<u16> = distances
+            .iter()
+            .map(|z| (far * (z - near) / (z * (far - near)) * 65535.0_f32).round() as u16)
+            .collect();
+        assert!(matches!(
+            Texture2D::from_depth16(4, 1, &depth),
+            Err(crate::Error::InvalidBufferLength(_, 4, 5))
+        ));
+        let tex = Texture2D::from_depth16(5, 1, &depth).unwrap();
+        assert_eq!((tex.width, tex.height), (5, 1));
+        assert_eq!(tex.data, TextureData::RU16(depth));
+        assert_eq!(tex.mip_map_filter, None);
+
+        let TextureData::RU8(gray) = tex.visualize_depth(near, far).data else {
+            panic!("expected RU8 data");
+        };
+        assert_eq!(gray[0], 0);
+        assert_eq!(gray[4], 255);
+        for (i, expected) in [64, 128, 191].into_iter().enumerate() {
+            assert!((gray[i + 1] as i32 - expected).abs() <= 2);
+        }
+    }
+
+    #[test]
+    pub fn height_f32() {
+        let tex = Texture2D {
+            data: TextureData::RU8(vec![0, 51, 102, 153, 204, 255]),
+            width: 3,
+            height: 2,
+            ..Default::default()
+        };
+        assert_eq!(tex.height_f32(), vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
+
+        let tex = Texture2D {
+            data: TextureData::RgbaF32(vec![[0.5, 0.5, 0.5, 0.0], [1.0, 0.0, 0.0, 1.0]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        assert_eq!(tex.height_f32(), vec![0.5, 0.2126]);
+    }
+
+    #[test]
+    pub fn to_luminance_alpha() {
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![
+                [255, 255, 255, 0],
+                [255, 0, 0, 128],
+                [0, 255, 0, 255],
+                [100, 100, 100, 7],
+            ]),
+            width: 2,
+            height: 2,
+            ..Default::default()
+        };
+        let luminance_alpha = tex.to_luminance_alpha();
+        assert_eq!((luminance_alpha.width, luminance_alpha.height), (2, 2));
+        assert_eq!(
+            luminance_alpha.data,
+            TextureData::RgU8(vec![[255, 0], [54, 128], [182, 255], [100, 7]])
+        );
+
+        let tex = Texture2D {
+            data: TextureData::RU8(vec![51]),
+            width: 1,
+            height: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            tex.to_luminance_alpha().data,
+            TextureData::RgU8(vec![[51, 255]])
+        );
+    }
+
+    #[test]
+    pub fn to_sdf() {
+        let size = 64;
+        let radius = 20.0;
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(
+                (0..size * size)
+                    .map(|i| {
+                        let (x, y) = ((i % size) as f32 + 0.5, (i / size) as f32 + 0.5);
+                        let inside = (x - 32.0).powi(2) + (y - 32.0).powi(2) <= radius * radius;
+                        [255, 255, 255, if inside { 255 } else { 0 }]
+                    })
+                    .collect(),
+            ),
+            width: size,
+            height: size,
+            ..Default::default()
+        };
+        let sdf = tex.to_sdf(8.0);
+        assert_eq!((sdf.width, sdf.height), (size, size));
+        let TextureData::RU8(values) = sdf.data else {
+            panic!("expected RU8 data");
+        };
+        assert_eq!(values[32 * size as usize + 32], 255);
+        assert_eq!(values[0], 0);
+        for angle in 0..16 {
+            let angle = angle as f32 * std::f32::consts::PI / 8.0;
+            let x = (32.0 + radius * angle.cos()) as usize;
+            let y = (32.0 + radius * angle.sin()) as usize;
+            assert!(values[y * size as usize + x].abs_diff(128) <= 16);
+        }
+    }
+
+    #[test]
+    pub fn to_f32_buffer() {
+        let tex = Texture2D {
+            data: TextureData::RgbU8(vec![[0, 51, 255], [255, 0, 102]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            tex.to_f32_buffer(4),
+            vec![0.0, 0.2, 1.0, 1.0, 1.0, 0.0, 0.4, 1.0]
+        );
+        assert_eq!(tex.to_f32_buffer(2), vec![0.0, 1.0, 1.0, 1.0]);
+        assert_eq!(tex.to_f32_buffer(5).len(), 10);
+    }
+
+    #[test]
+    pub fn ascii_preview() {
+        let tex = Texture2D {
+            data: TextureData::RgbU8(vec![[255, 255, 255]; 64]),
+            width: 8,
+            height: 8,
+            ..Default::default()
+        };
+        assert_eq!(tex.ascii_preview(4, 2), "@@@@\n@@@@\n");
+
+        let tex = Texture2D {
+            data: TextureData::RU8(vec![0, 255]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        assert_eq!(tex.ascii_preview(2, 1), " @\n");
+        assert_eq!(tex.ascii_preview(4, 2), "  @@\n  @@\n");
+    }
+
+    #[test]
+    pub fn power_of_two() {
+        let size = |width, height| Texture2D {
+            data: TextureData::RU8(vec![0; (width * height) as usize]),
+            width,
+            height,
+            ..Default::default()
+        };
+        assert!(size(256, 256).is_power_of_two());
+        assert_eq!(size(256, 256).next_power_of_two_dimensions(), (256, 256));
+        assert!(!size(100, 200).is_power_of_two());
+        assert_eq!(size(100, 200).next_power_of_two_dimensions(), (128, 256));
+        assert!(size(1, 1).is_power_of_two());
+        assert_eq!(size(1, 1).next_power_of_two_dimensions(), (1, 1));
+    }
+
+    #[test]
+    pub fn mipmaps() {
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 0, 0, 255]; 256 * 256]),
+            width: 256,
+            height: 256,
+            ..Default::default()
+        };
+        assert!(tex.mipmaps_supported());
+        let levels = tex.generate_mipmaps(DownsampleKernel::Box).unwrap();
+        assert_eq!(levels.len(), 8);
+        assert_eq!((levels[0].width, levels[0].height), (128, 128));
+        assert_eq!((levels[7].width, levels[7].height), (1, 1));
+        assert_eq!(levels[7].data, TextureData::RgbaU8(vec![[255, 0, 0, 255]]));
+
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 0, 0, 255], [0, 0, 255, 255]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        let levels = tex.generate_mipmaps(DownsampleKernel::Box).unwrap();
+        assert_eq!(levels.len(), 1);
+        assert_eq!(
+            levels[0].data,
+            TextureData::RgbaU8(vec![[128, 0, 128, 255]])
+        );
+
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 0, 0, 255]; 100 * 100]),
+            width: 100,
+            height: 100,
+            ..Default::default()
+        };
+        assert!(!tex.mipmaps_supported());
+        assert!(matches!(
+            tex.generate_mipmaps(DownsampleKernel::Box),
+            Err(crate::Error::MipmapUnsupported {
+                width: 100,
+                height: 100
+            })
+        ));
+    }
+
+    #[test]
+    pub fn mipmap_kernels() {
+        // A high frequency pattern which changes from texel to texel.
+        let tex = Texture2D::from_fn_f32(16, 16, |x, y| {
+            let v = ((x * 7 + y * 13) % 5) as f32 / 4.0;
+            [v, v, v, 1.0]
+        })
+        .with_wrapping(Wrapping::Repeat, Wrapping::Repeat);
+        let box_levels = tex.generate_mipmaps(DownsampleKernel::Box).unwrap();
+        let lanczos_levels = tex.generate_mipmaps(DownsampleKernel::Lanczos).unwrap();
+        assert_eq!(box_levels.len(), lanczos_levels.len());
+        assert_ne!(box_levels[0].data, lanczos_levels[0].data);
+        for level in box_levels.iter().chain(lanczos_levels.iter()) {
+            assert!(level.data.iter_rgba_f32().flatten().all(|v| v.is_finite()));
+        }
+
+        // A sharp alpha edge makes the negative lobes of the Lanczos and Kaiser kernels ring.
+        let tex = Texture2D::from_fn_f32(16, 16, |x, _| {
+            let a = if (4..8).contains(&x) { 1.0 } else { 0.0 };
+            [1.0, 1.0, 1.0, a]
+        });
+        for kernel in [DownsampleKernel::Kaiser, DownsampleKernel::Lanczos] {
+            for level in tex.generate_mipmaps(kernel).unwrap() {
+                assert!(level.data.iter_rgba_f32().all(|c| c[3] >= 0.0));
+            }
+        }
+
+        // All kernels preserve a constant color.
+        let tex = Texture2D::from_fn(8, 8, |_, _| [200, 100, 50, 255]);
+        for kernel in [
+            DownsampleKernel::Box,
+            DownsampleKernel::Triangle,
+            DownsampleKernel::Kaiser,
+            DownsampleKernel::Lanczos,
+        ] {
+            let levels = tex.generate_mipmaps(kernel).unwrap();
+            assert_eq!(
+                levels[2].data,
+                TextureData::RgbaU8(vec![[200, 100, 50, 255]])
+            );
+        }
+    }
+
+    #[test]
+    pub fn mipmaps_do_not_darken_edges() {
+        // A single white opaque texel surrounded by transparent black texels.
+        let mut texels = vec![[0.0, 0.0, 0.0, 0.0]; 16];
+        texels[5] = [1.0; 4];
+        let tex = Texture2D {
+            data: TextureData::RgbaF32(texels),
+            width: 4,
+            height: 4,
+            ..Default::default()
+        };
+        let levels = tex.generate_mipmaps(DownsampleKernel::Box).unwrap();
+        assert_eq!(
+            levels[0].data,
+            TextureData::RgbaF32(vec![[1.0, 1.0, 1.0, 0.25], [0.0; 4], [0.0; 4], [0.0; 4]])
+        );
+        assert_eq!(
+            levels[1].data,
+            TextureData::RgbaF32(vec![[1.0, 1.0, 1.0, 0.0625]])
+        );
+
+        let mut texels = vec![[0, 0, 0, 0]; 16];
+        texels[5] = [255; 4];
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(texels),
+            width: 4,
+            height: 4,
+            ..Default::default()
+        };
+        let levels = tex.generate_mipmaps(DownsampleKernel::Box).unwrap();
+        assert_eq!(
+            levels[1].data,
+            TextureData::RgbaU8(vec![[255, 255, 255, 16]])
+        );
+    }
+
+    #[test]
+    pub fn difference() {
+        let a = Texture2D {
+            data: TextureData::RgbaU8(vec![[10, 20, 30, 40], [255, 0, 255, 0]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            a.difference(&a).unwrap().data,
+            TextureData::RgbaU8(vec![[0, 0, 0, 0]; 2])
+        );
+        let b = Texture2D {
+            data: TextureData::RgbaU8(vec![[20, 10, 30, 40], [0, 0, 255, 255]]),
+            ..a.clone()
+        };
+        assert_eq!(
+            a.difference(&b).unwrap().data,
+            TextureData::RgbaU8(vec![[10, 10, 0, 0], [255, 0, 0, 255]])
+        );
+        assert!(a.difference(&Texture2D::default()).is_err());
+    }
+
+    #[test]
+    pub fn rmse() {
+        let a = Texture2D {
+            data: TextureData::RgU8(vec![[0, 255], [51, 255], [102, 0], [255, 255]]),
+            width: 2,
+            height: 2,
+            ..Default::default()
+        };
+        assert_eq!(a.rmse(&a).unwrap(), 0.0);
+        assert_eq!(a.psnr(&a).unwrap(), f32::INFINITY);
+
+        // Only the first channel of the first texel differs by 0.4 in 8 values.
+        let b = Texture2D {
+            data: TextureData::RgU8(vec![[102, 255], [51, 255], [102, 0], [255, 255]]),
+            ..a.clone()
+        };
+        let expected = (0.4f32 * 0.4 / 8.0).sqrt();
+        assert!((a.rmse(&b).unwrap() - expected).abs() < 1e-6);
+        assert!((a.psnr(&b).unwrap() + 20.0 * expected.log10()).abs() < 1e-4);
+        assert!(a.rmse(&a.crop(0, 0, 1, 1)).is_err());
+    }
+
+    #[test]
+    pub fn dimensions_mismatch() {
+        let tex = Texture2D {
+            data: TextureData::RU8(vec![0; 6]),
+            width: 3,
+            height: 2,
+            ..Default::default()
+        };
+        assert_eq!(tex.dimensions(), (3, 2));
+        assert!(matches!(
+            tex.difference(&Texture2D::default()),
+            Err(crate::Error::TextureSizeMismatch(1, 1, 3, 2))
+        ));
+    }
+
+    #[test]
+    pub fn mix() {
+        let black = Texture2D {
+            data: TextureData::RgbU8(vec![[0, 0, 0]; 4]),
+            width: 2,
+            height: 2,
+            ..Default::default()
+        };
+        let white = Texture2D {
+            data: TextureData::RgbaF32(vec![[1.0; 4]; 4]),
+            ..black.clone()
+        };
+        assert_eq!(
+            black.mix(&white, 0.5).unwrap().data,
+            TextureData::RgbU8(vec![[128, 128, 128]; 4])
+        );
+        assert_eq!(black.mix(&white, -1.0).unwrap().data, black.data);
+        assert_eq!(
+            black.mix(&white, 2.0).unwrap().data,
+            TextureData::RgbU8(vec![[255, 255, 255]; 4])
+        );
+        assert!(black.mix(&Texture2D::default(), 0.5).is_err());
+    }
+
+    #[test]
+    pub fn blend_over() {
+        let red = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 0, 0, 128]]),
+            ..Default::default()
+        };
+        let white = Texture2D {
+            data: TextureData::RgbU8(vec![[255, 255, 255]]),
+            ..Default::default()
+        };
+        assert_eq!(
+            red.blend_over(&white).unwrap().data,
+            TextureData::RgbU8(vec![[255, 127, 127]])
+        );
+
+        let transparent = Texture2D {
+            data: TextureData::RgbaU8(vec![[0, 0, 0, 0]]),
+            ..Default::default()
+        };
+        assert_eq!(
+            red.blend_over(&transparent).unwrap().data,
+            TextureData::RgbaU8(vec![[255, 0, 0, 128]])
+        );
+    }
+
+    #[test]
+    pub fn composite() {
+        let background = Texture2D::from_fn(2, 2, |x, y| [x as u8 * 255, y as u8 * 255, 100, 255]);
+        let red = Texture2D::from_fn(2, 2, |_, _| [255, 0, 0, 128]);
+        let green = Texture2D::from_fn(2, 2, |x, _| [0, 255, 0, 64 + x as u8 * 100]);
+        let blue = Texture2D::from_fn(2, 2, |_, y| [0, 0, 255, 32 + y as u8 * 150]);
+
+        let composited = Texture2D::composite(&[&background, &red, &green, &blue]).unwrap();
+        let sequential = blue
+            .blend_over(
+                &green
+                    .blend_over(&red.blend_over(&background).unwrap())
+                    .unwrap(),
+            )
+            .unwrap();
+        let (TextureData::RgbaU8(a), TextureData::RgbaU8(b)) = (&composited.data, &sequential.data)
+        else {
+            panic!("expected RgbaU8 data");
+        };
+        for (a, b) in a.iter().zip(b.iter()) {
+            for c in 0..4 {
+                assert!((a[c] as i32 - b[c] as i32).abs() <= 1);
+            }
+        }
+
+        let float = |tex: &Texture2D| Texture2D {
+            data: TextureData::RgbaF32(tex.data.iter_rgba_f32().collect()),
+            ..tex.clone()
+        };
+        let (background, red, green) = (float(&background), float(&red), float(&green));
+        let composited = Texture2D::composite(&[&background, &red, &green]).unwrap();
+        let sequential = green
+            .blend_over(&red.blend_over(&background).unwrap())
+            .unwrap();
+        for (a, b) in composited
+            .data
+            .iter_rgba_f32()
+            .zip(sequential.data.iter_rgba_f32())
+        {
+            for c in 0..4 {
+                assert!((a[c] - b[c]).abs() < 1e-5);
+            }
+        }
+
+        let empty = Texture2D::composite(&[]).unwrap();
+        assert_eq!((empty.width, empty.height), (0, 0));
+        assert_eq!(empty.data, TextureData::RgbaU8(Vec::new()));
+        assert!(matches!(
+            Texture2D::composite(&[&background, &Texture2D::default()]),
+            Err(crate::Error::TextureSizeMismatch(1, 1, 2, 2))
+        ));
+    }
+
+    #[test]
+    pub fn flatten_onto() {
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 255, 255, 128], [255, 0, 0, 255]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        let flattened = tex.flatten_onto([0, 0, 0, 255]);
+        assert_eq!(
+            flattened.data,
+            TextureData::RgbU8(vec![[128, 128, 128], [255, 0, 0]])
+        );
+        assert_eq!(flattened.width, 2);
+    }
+
+    #[test]
+    pub fn overlay_checkerboard() {
+        let mut tex = Texture2D::from_fn(4, 4, |_, _| [128, 64, 32, 200]);
+        tex.overlay_checkerboard(2, 1.0);
+        let TextureData::RgbaU8(data) = &tex.data else {
+            panic!("expected RgbaU8 data");
+        };
+        assert_eq!(data[0], [255, 255, 255, 200]);
+        assert_eq!(data[5], [255, 255, 255, 200]);
+        assert_eq!(data[2], [0, 0, 0, 200]);
+        assert_eq!(data[2 * 4], [0, 0, 0, 200]);
+        assert_eq!(data[3 * 4 + 3], [255, 255, 255, 200]);
+
+        let mut tex = Texture2D::from_fn_f32(2, 1, |_, _| [0.5, 0.5, 0.5, 1.0]);
+        tex.overlay_checkerboard(1, 0.5);
+        assert_eq!(
+            tex.data,
+            TextureData::RgbaF32(vec![[0.75, 0.75, 0.75, 1.0], [0.25, 0.25, 0.25, 1.0]])
+        );
+    }
+
+    #[test]
+    pub fn zero_transparent_rgb() {
+        let mut tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[255, 0, 0, 0], [10, 20, 30, 255], [40, 50, 60, 1]]),
+            width: 3,
+            height: 1,
+            ..Default::default()
+        };
+        tex.zero_transparent_rgb();
+        assert_eq!(
+            tex.data,
+            TextureData::RgbaU8(vec![[0, 0, 0, 0], [10, 20, 30, 255], [40, 50, 60, 1]])
+        );
+        assert!(!tex.premultiplied);
+
+        let mut tex = Texture2D {
+            data: TextureData::RgbaF32(vec![[0.5, 0.5, 0.5, 0.0], [0.25, 0.5, 0.75, 1.0]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        tex.zero_transparent_rgb();
+        assert_eq!(
+            tex.data,
+            TextureData::RgbaF32(vec![[0.0; 4], [0.25, 0.5, 0.75, 1.0]])
+        );
+    }
+
+    #[test]
+    pub fn threshold() {
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![
+                [255, 0, 0, 0],
+                [0, 255, 0, 127],
+                [0, 0, 255, 128],
+                [255, 255, 255, 255],
+            ]),
+            width: 2,
+            height: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            tex.threshold(3, 0.5, false).unwrap().data,
+            TextureData::RU8(vec![0, 0, 255, 255])
+        );
+        assert_eq!(
+            tex.threshold(3, 0.5, true).unwrap().data,
+            TextureData::RU8(vec![255, 255, 0, 0])
+        );
+        assert_eq!(
+            tex.threshold(1, 0.5, false).unwrap().data,
+            TextureData::RU8(vec![0, 255, 0, 255])
+        );
+        assert!(matches!(
+            tex.threshold(4, 0.5, false),
+            Err(crate::Error::InvalidChannelIndex(4, 4))
+        ));
+    }
+
+    #[test]
+    pub fn extend_edges() {
+        let tex = |wrap_s| Texture2D {
+            data: TextureData::RU8(vec![1, 2, 3]),
+            width: 3,
+            height: 1,
+            wrap_s,
+            wrap_t: Wrapping::ClampToEdge,
+            ..Default::default()
+        };
+        let row = |tex: &Texture2D| match &tex.data {
+            TextureData::RU8(data) => {
+                data[(2 * tex.width) as usize..(3 * tex.width) as usize].to_vec()
+            }
+            _ => panic!("Wrong texture data: {:?}", tex.data),
+        };
+
+        let extended = tex(Wrapping::Repeat).extend_edges(2);
+        assert_eq!((extended.width, extended.height), (7, 5));
+        assert_eq!(row(&extended), vec![2, 3, 1, 2, 3, 1, 2]);
+        assert_eq!(
+            row(&tex(Wrapping::MirroredRepeat).extend_edges(2)),
+            vec![2, 1, 1, 2, 3, 3, 2]
+        );
+        assert_eq!(
+            row(&tex(Wrapping::ClampToEdge).extend_edges(2)),
+            vec![1, 1, 1, 2, 3, 3, 3]
+        );
+
+        let extended = Texture2D {
+            data: TextureData::RU8(vec![1, 2]),
+            width: 1,
+            height: 2,
+            wrap_t: Wrapping::Repeat,
+            ..Default::default()
+        }
+        .extend_edges(1);
+        assert_eq!(
+            extended.data,
+            TextureData::RU8(vec![2, 2, 2, 1, 1, 1, 2, 2, 2, 1, 1, 1])
+        );
+    }
+
+    #[test]
+    pub fn srgb_aware_resize() {
+        let tex = Texture2D {
+            data: TextureData::RgbU8(vec![[0, 0, 0], [255, 255, 255]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            tex.resize(1, 1, Interpolation::Linear).data,
+            TextureData::RgbU8(vec![[128, 128, 128]])
+        );
+        assert_eq!(
+            tex.srgb_aware_resize(1, 1, Interpolation::Linear).data,
+            TextureData::RgbU8(vec![[188, 188, 188]])
+        );
+    }
+
+    #[test]
+    pub fn channel() {
+        let tex = Texture2D {
+            data: TextureData::RgbaU8(vec![[1, 2, 3, 4], [5, 6, 7, 8]]),
+            width: 2,
+            height: 1,
+            ..Default::default()
+        };
+        let green = tex.channel(1).unwrap();
+        assert_eq!(green.data, TextureData::RU8(vec![2, 6]));
+        assert_eq!((green.width, green.height), (2, 1));
+        assert_eq!(tex.channel(3).unwrap().data, TextureData::RU8(vec![4, 8]));
+        assert!(matches!(
+            tex.channel(4),
+            Err(crate::Error::InvalidChannelIndex(4, 4))
+        ));
+    }
+
+    #[test]
+    pub fn pack_orm() {
+        let ramp = |data: TextureData| Texture2D {
+            data,
+            width: 4,
+            height: 1,
+            ..Default::default()
+        };
+        let occlusion = ramp(TextureData::RU8(vec![0, 85, 170, 255]));
+        let roughness = ramp(TextureData::RgbU8(vec![
+            [10, 10, 10],
+            [20, 20, 20],
+            [30, 30, 30],
+            [40, 40, 40],
+        ]));
+        let metallic = ramp(TextureData::RF32(vec![1.0, 0.8, 0.2, 0.0]));
+        let orm = Texture2D::pack_orm(&occlusion, &roughness, &metallic).unwrap();
+        assert_eq!((orm.width, orm.height), (4, 1));
+        assert_eq!(
+            orm.data,
+            TextureData::RgbU8(vec![
+                [0, 10, 255],
+                [85, 20, 204],
+                [170, 30, 51],
+                [255, 40, 0]
+            ])
+        );
+        assert!(matches!(
+            Texture2D::pack_orm(&occlusion, &roughness, &orm.crop(0, 0, 2, 1)),
+            Err(crate::Error::TextureSizeMismatch(2, 1, 4, 1))
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+use crate::texture::Texture2D;
+#[doc(inline)]
+pub use crate::texture::{Interpolation, TextureData, Wrapping};
+
+///
+/// A CPU-side version of a 2D texture array, ie. a stack of 2D textures with the same size and format,
+/// for example used for terrain splatting or sprite layers.
+/// In contrast to a [Texture3D](crate::Texture3D), the texture is never interpolated between the layers.
+///
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Texture2DArray {
+    /// Name of this texture.
+    pub name: String,
+    /// The pixel data for all layers, one layer at a time starting with the first layer.
+    pub data: TextureData,
+    /// The width of each layer
+    pub width: u32,
+    /// The height of each layer
+    pub height: u32,
+    /// The number of layers
+    pub layers: u32,
+    /// The way the pixel data is interpolated when the texture is far away
+    pub min_filter: Interpolation,
+    /// The way the pixel data is interpolated when the texture is close
+    pub mag_filter: Interpolation,
+    /// Specifies whether mipmaps should be created for each layer of this texture and what type of interpolation to use between the two closest mipmaps.
+    pub mip_map_filter: Option<Interpolation>,
+    /// Determines how each layer is sampled outside the [0..1] s coordinate range (the first value of the uv coordinates).
+    pub wrap_s: Wrapping,
+    /// Determines how each layer is sampled outside the [0..1] t coordinate range (the second value of the uv coordinates).
+    pub wrap_t: Wrapping,
+}
+
+impl Default for Texture2DArray {
+    fn default() -> Self {
+        Self {
+            name: "default".to_owned(),
+            data: TextureData::RgbaU8(vec![[0, 0, 0, 0]]),
+            width: 1,
+            height: 1,
+            layers: 1,
+            min_filter: Interpolation::Linear,
+            mag_filter: Interpolation::Linear,
+            mip_map_filter: Some(Interpolation::Linear),
+            wrap_s: Wrapping::Repeat,
+            wrap_t: Wrapping::Repeat,
+        }
+    }
+}
+
+impl Texture2DArray {
+    ///
+    /// Constructs a texture array with the given textures as layers, in the given order.
+    /// The name, filters and wrapping are taken from the first texture.
+    ///
+    /// Returns a [TextureSizeMismatch](crate::Error::TextureSizeMismatch) error if the textures do not have the same size
+    /// and a [TextureDataFormatMismatch](crate::Error::TextureDataFormatMismatch) error if they do not have the same [TextureData] variant.
+    ///
+    pub fn from_slices(slices: &[Texture2D]) -> crate::Result<Self> {
+        let first = match slices.first() {
+            Some(first) => first,
+            None => {
+                return Ok(Self {
+                    data: TextureData::RgbaU8(Vec::new()),
+                    width: 0,
+                    height: 0,
+                    layers: 0,
+                    ..Default::default()
+                })
+            }
+        };
+        let mut data = first.data.clone();
+        for slice in &slices[1..] {
+            if slice.dimensions() != first.dimensions() {
+                return Err(crate::Error::TextureSizeMismatch(
+                    slice.width,
+                    slice.height,
+                    first.width,
+                    first.height,
+                ));
+            }
+            macro_rules! append {
+                ($($variant:ident),*) => {
+                    match (&mut data, &slice.data) {
+                        $((TextureData::$variant(data), TextureData::$variant(values)) => {
+                            data.extend_from_slice(values)
+                        })*
+                        _ => return Err(crate::Error::TextureDataFormatMismatch),
+                    }
+                };
+            }
+            append!(
+                RU8, RgU8, RgbU8, RgbaU8, RU16, RgU16, RgbU16, RgbaU16, RF16, RgF16, RgbF16,
+                RgbaF16, RF32, RgF32, RgbF32, RgbaF32
+            );
+        }
+        Ok(Self {
+            name: first.name.clone(),
+            data,
+            width: first.width,
+            height: first.height,
+            layers: slices.len() as u32,
+            min_filter: first.min_filter,
+            mag_filter: first.mag_filter,
+            mip_map_filter: first.mip_map_filter,
+            wrap_s: first.wrap_s,
+            wrap_t: first.wrap_t,
+        })
+    }
+
+    ///
+    /// Returns a copy of the layer with the given index as a [Texture2D] with the same name, filters and wrapping as this texture array
+    /// or `None` if the index is out of range.
+    ///
+    pub fn layer(&self, index: u32) -> Option<Texture2D> {
+        if index >= self.layers {
+            return None;
+        }
+        let size = self.width as usize * self.height as usize;
+        let range = index as usize * size..(index as usize + 1) * size;
+        macro_rules! layer {
+            ($($variant:ident),*) => {
+                match &self.data {
+                    $(TextureData::$variant(values) => TextureData::$variant(values.get(range)?.to_vec()),)*
+                }
+            };
+        }
+        let data = layer!(
+            RU8, RgU8, RgbU8, RgbaU8, RU16, RgU16, RgbU16, RgbaU16, RF16, RgF16, RgbF16, RgbaF16,
+            RF32, RgF32, RgbF32, RgbaF32
+        );
+        Some(Texture2D {
+            name: self.name.clone(),
+            data,
+            width: self.width,
+            height: self.height,
+            min_filter: self.min_filter,
+            mag_filter: self.mag_filter,
+            mip_map_filter: self.mip_map_filter,
+            wrap_s: self.wrap_s,
+            wrap_t: self.wrap_t,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn from_slices() {
+        let layer = |value: u8| Texture2D::from_fn(4, 2, move |_, _| [value, 0, 0, 255]);
+        let array = Texture2DArray::from_slices(&[layer(0), layer(100), layer(200)]).unwrap();
+        assert_eq!((array.width, array.height, array.layers), (4, 2, 3));
+        assert_eq!(array.data.len(), 4 * 2 * 3);
+        for (i, value) in [0, 100, 200].into_iter().enumerate() {
+            let tex = array.layer(i as u32).unwrap();
+            assert_eq!((tex.width, tex.height), (4, 2));
+            assert_eq!(tex.data, layer(value).data);
+        }
+        assert!(array.layer(3).is_none());
+
+        assert!(matches!(
+            Texture2DArray::from_slices(&[layer(0), Texture2D::from_fn(2, 2, |_, _| [0; 4])]),
+            Err(crate::Error::TextureSizeMismatch(2, 2, 4, 2))
+        ));
+        assert!(matches!(
+            Texture2DArray::from_slices(&[layer(0), Texture2D::from_fn_f32(4, 2, |_, _| [0.0; 4])]),
+            Err(crate::Error::TextureDataFormatMismatch)
+        ));
+        assert_eq!(Texture2DArray::from_slices(&[]).unwrap().layers, 0);
+    }
+}
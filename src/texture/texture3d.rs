@@ -49,3 +49,120 @@ impl Default for Texture3D {
         }
     }
 }
+
+impl Texture3D {
+    ///
+    /// Returns a copy of this texture resized to the given width, height and depth using the given interpolation,
+    /// for example to fit a volume within the texture size limits of a GPU.
+    /// Linear interpolation is trilinear and [Interpolation::CubicSpline] is treated as linear.
+    /// The texels outside the texture are clamped to the edge and the result has the same [TextureData] format as this texture.
+    /// If this texture has zero width, height or depth, the result is an empty volume where all texels are transparent black.
+    ///
+    pub fn resize(
+        &self,
+        width: u32,
+        height: u32,
+        depth: u32,
+        interpolation: Interpolation,
+    ) -> Texture3D {
+        if self.width == 0 || self.height == 0 || self.depth == 0 {
+            let count = width as usize * height as usize * depth as usize;
+            return Texture3D {
+                data: self.data.same_format_from_rgba_f32(vec![[0.0; 4]; count]),
+                width,
+                height,
+                depth,
+                ..self.clone()
+            };
+        }
+        let scale = [
+            self.width as f32 / width as f32,
+            self.height as f32 / height as f32,
+            self.depth as f32 / depth as f32,
+        ];
+        let texel = |x: i64, y: i64, z: i64| {
+            let x = x.clamp(0, self.width as i64 - 1) as usize;
+            let y = y.clamp(0, self.height as i64 - 1) as usize;
+            let z = z.clamp(0, self.depth as i64 - 1) as usize;
+            let (width, height) = (self.width as usize, self.height as usize);
+            self.data.rgba_f32((z * height + y) * width + x)
+        };
+        let texels = (0..depth).flat_map(|z| {
+            (0..height).flat_map(move |y| {
+                (0..width).map(move |x| {
+                    let position = [x, y, z].map(|v| v as f32);
+                    let [x, y, z] = [0, 1, 2].map(|i| (position[i] + 0.5) * scale[i] - 0.5);
+                    match interpolation {
+                        Interpolation::Nearest => {
+                            texel(x.round() as i64, y.round() as i64, z.round() as i64)
+                        }
+                        Interpolation::Linear | Interpolation::CubicSpline => {
+                            let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+                            let (tx, ty, tz) = (x - x0, y - y0, z - z0);
+                            let (x0, y0, z0) = (x0 as i64, y0 as i64, z0 as i64);
+                            let mut color = [0.0; 4];
+                            for (dz, wz) in [(0, 1.0 - tz), (1, tz)] {
+                                for (dy, wy) in [(0, 1.0 - ty), (1, ty)] {
+                                    for (dx, wx) in [(0, 1.0 - tx), (1, tx)] {
+                                        let c = texel(x0 + dx, y0 + dy, z0 + dz);
+                                        for i in 0..4 {
+                                            color[i] += c[i] * wx * wy * wz;
+                                        }
+                                    }
+                                }
+                            }
+                            color
+                        }
+                    }
+                })
+            })
+        });
+        Texture3D {
+            data: self.data.same_format_from_rgba_f32(texels),
+            width,
+            height,
+            depth,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn resize() {
+        // Alternating layers of black and white voxels along the depth.
+        let tex = Texture3D {
+            data: TextureData::RU8(
+                (0..64)
+                    .map(|i| if (i / 16) % 2 == 0 { 0 } else { 255 })
+                    .collect(),
+            ),
+            width: 4,
+            height: 4,
+            depth: 4,
+            ..Default::default()
+        };
+        let linear = tex.resize(2, 2, 2, Interpolation::Linear);
+        assert_eq!((linear.width, linear.height, linear.depth), (2, 2, 2));
+        assert_eq!(linear.data, TextureData::RU8(vec![128; 8]));
+
+        let nearest = tex.resize(2, 2, 2, Interpolation::Nearest);
+        assert_eq!(nearest.data, TextureData::RU8(vec![255; 8]));
+
+        let same = tex.resize(4, 4, 4, Interpolation::Linear);
+        assert_eq!(same.data, tex.data);
+
+        let empty = Texture3D {
+            data: TextureData::RU8(Vec::new()),
+            width: 4,
+            height: 0,
+            depth: 4,
+            ..Default::default()
+        };
+        let resized = empty.resize(2, 2, 2, Interpolation::Linear);
+        assert_eq!(resized.data, TextureData::RU8(vec![0; 8]));
+    }
+}